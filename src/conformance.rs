@@ -0,0 +1,319 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A reusable test suite checking `StringExt` implementations against
+//! `std::string::String`.
+
+/// Expands to a battery of `#[test]` functions checking that a `StringExt`
+/// implementation behaves exactly like `std::string::String`.
+///
+/// Every operation is run on both a `String` and the given type, starting from
+/// the same contents, and the results, the resulting contents, and whether or
+/// not the operation panicked must all agree. The inputs cover the empty
+/// string, multi-byte characters, every byte index (character boundary or
+/// not), and lengths on either side of `INLINE_STRING_CAPACITY`.
+///
+/// The type must implement `StringExt`, `Debug` and `Sized`. Invoke the macro
+/// inside its own module, since it defines a number of helper items alongside
+/// the tests.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate inlinable_string;
+///
+/// #[cfg(test)]
+/// mod conformance {
+///     use inlinable_string::InlinableString;
+///
+///     string_ext_conformance_tests!(InlinableString);
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! string_ext_conformance_tests {
+    ($t:ty) => {
+        fn samples() -> ::std::vec::Vec<::std::string::String> {
+            let cap = $crate::INLINE_STRING_CAPACITY;
+            vec![
+                "".into(),
+                "a".into(),
+                "héllo".into(),
+                "𝄞 music\u{0}".into(),
+                "x".repeat(cap - 1),
+                "x".repeat(cap),
+                "x".repeat(cap - 2) + "é",
+                "x".repeat(cap - 1) + "é",
+                "αβγ 𝄞 ".repeat(4),
+            ]
+        }
+
+        fn make(input: &str) -> $t {
+            let mut s = <$t as $crate::StringExt>::new();
+            $crate::StringExt::push_str(&mut s, input);
+            s
+        }
+
+        /// Runs `expected` on a `String` and `actual` on the type under test,
+        /// both starting out as `input`, and checks that they agree.
+        fn check<R, F, G>(input: &str, what: &str, expected: F, actual: G)
+            where R: PartialEq + ::std::fmt::Debug,
+                  F: FnOnce(&mut ::std::string::String) -> R + ::std::panic::UnwindSafe,
+                  G: FnOnce(&mut $t) -> R + ::std::panic::UnwindSafe
+        {
+            let expected = ::std::panic::catch_unwind(move || {
+                let mut s = ::std::string::String::from(input);
+                let result = expected(&mut s);
+                (result, s)
+            });
+            let actual = ::std::panic::catch_unwind(move || {
+                let mut s = make(input);
+                let result = actual(&mut s);
+                let contents = ::std::string::String::from($crate::StringExt::as_str(&s));
+                assert!($crate::StringExt::capacity(&s) >= contents.len(),
+                        "capacity {} is less than length {}",
+                        $crate::StringExt::capacity(&s), contents.len());
+                (result, contents)
+            });
+            match (expected, actual) {
+                (Ok(expected), Ok(actual)) => {
+                    assert_eq!(expected, actual, "{} on {:?}", what, input)
+                }
+                (Err(_), Err(_)) => {}
+                (Ok(_), Err(_)) => panic!("{} on {:?} panicked, but not for String", what, input),
+                (Err(_), Ok(_)) => panic!("{} on {:?} panicked for String only", what, input),
+            }
+        }
+
+        #[test]
+        fn conformance_constructors() {
+            let s = <$t as $crate::StringExt>::new();
+            assert!($crate::StringExt::is_empty(&s));
+            for &n in &[0, 1, 10, $crate::INLINE_STRING_CAPACITY + 1, 100] {
+                let s = <$t as $crate::StringExt>::with_capacity(n);
+                assert!($crate::StringExt::is_empty(&s));
+                assert!($crate::StringExt::capacity(&s) >= n);
+                let s = <$t as $crate::StringExt>::try_with_capacity(n).unwrap();
+                assert!($crate::StringExt::capacity(&s) >= n);
+            }
+            assert!(<$t as $crate::StringExt>::try_with_capacity(usize::MAX).is_err());
+        }
+
+        #[test]
+        fn conformance_from_utf8() {
+            let mut inputs: ::std::vec::Vec<::std::vec::Vec<u8>> =
+                samples().into_iter().map(|s| s.into_bytes()).collect();
+            inputs.push(b"abc\xFF".to_vec());
+            inputs.push(b"\xF0\x9D\x84".to_vec());
+            inputs.push(b"\xC0\x80 and then some more text after it".to_vec());
+            for bytes in inputs {
+                let expected = ::std::string::String::from_utf8(bytes.clone()).ok();
+                let actual = <$t as $crate::StringExt>::from_utf8(bytes.clone()).ok();
+                assert_eq!(expected.as_ref().map(|s| s.as_str()),
+                           actual.as_ref().map($crate::StringExt::as_str));
+
+                let expected = ::std::string::String::from_utf8_lossy(&bytes).into_owned();
+                let actual = <$t as $crate::StringExt>::from_utf8_lossy_owned(bytes.clone());
+                assert_eq!(expected, $crate::StringExt::as_str(&actual));
+
+                let mut actual = make("");
+                $crate::StringExt::push_utf8_lossy(&mut actual, &bytes);
+                assert_eq!(expected, $crate::StringExt::as_str(&actual));
+            }
+        }
+
+        #[test]
+        fn conformance_from_utf16() {
+            let mut inputs: ::std::vec::Vec<::std::vec::Vec<u16>> =
+                samples().iter().map(|s| s.encode_utf16().collect()).collect();
+            inputs.push(vec![0xD834]);
+            inputs.push(vec![0x61, 0xDD1E, 0x62]);
+            for units in inputs {
+                let expected = ::std::string::String::from_utf16(&units).ok();
+                let actual = <$t as $crate::StringExt>::from_utf16(&units).ok();
+                assert_eq!(expected.as_ref().map(|s| s.as_str()),
+                           actual.as_ref().map($crate::StringExt::as_str));
+
+                let expected = ::std::string::String::from_utf16_lossy(&units);
+                let actual = <$t as $crate::StringExt>::from_utf16_lossy(&units);
+                assert_eq!(expected, $crate::StringExt::as_str(&actual));
+
+                let le: ::std::vec::Vec<u8> =
+                    units.iter().flat_map(|u| u.to_le_bytes().to_vec()).collect();
+                let be: ::std::vec::Vec<u8> =
+                    units.iter().flat_map(|u| u.to_be_bytes().to_vec()).collect();
+                let actual = <$t as $crate::StringExt>::from_utf16le_lossy(&le);
+                assert_eq!(expected, $crate::StringExt::as_str(&actual));
+                let actual = <$t as $crate::StringExt>::from_utf16be_lossy(&be);
+                assert_eq!(expected, $crate::StringExt::as_str(&actual));
+            }
+        }
+
+        #[test]
+        fn conformance_push_pop() {
+            for input in samples() {
+                for &ch in &['a', 'é', '€', '𝄞'] {
+                    for n in 0..12 {
+                        check(&input, "push", move |s| for _ in 0..n { s.push(ch) },
+                              move |s| for _ in 0..n { $crate::StringExt::push(s, ch) });
+                    }
+                }
+                for &string in &["", "b", "bé𝄞", "a much longer string pushed in one go"] {
+                    check(&input, "push_str", move |s| s.push_str(string),
+                          move |s| $crate::StringExt::push_str(s, string));
+                }
+                check(&input, "pop",
+                      |s| ::std::iter::from_fn(|| s.pop()).collect::<::std::string::String>(),
+                      |s| ::std::iter::from_fn(|| $crate::StringExt::pop(s))
+                              .collect::<::std::string::String>());
+                check(&input, "push/pop round trip",
+                      |s| { s.push('𝄞'); s.pop() },
+                      |s| { $crate::StringExt::push(s, '𝄞'); $crate::StringExt::pop(s) });
+            }
+        }
+
+        #[test]
+        fn conformance_truncate_clear() {
+            for input in samples() {
+                for i in 0..input.len() + 2 {
+                    check(&input, "truncate", move |s| s.truncate(i),
+                          move |s| $crate::StringExt::truncate(s, i));
+                }
+                check(&input, "clear", |s| s.clear(), |s| $crate::StringExt::clear(s));
+            }
+        }
+
+        #[test]
+        fn conformance_insert_remove() {
+            for input in samples() {
+                for i in 0..input.len() + 2 {
+                    check(&input, "insert", move |s| s.insert(i, 'é'),
+                          move |s| $crate::StringExt::insert(s, i, 'é'));
+                    check(&input, "insert_str", move |s| s.insert_str(i, "𝄞x"),
+                          move |s| $crate::StringExt::insert_str(s, i, "𝄞x"));
+                    check(&input, "remove", move |s| s.remove(i),
+                          move |s| $crate::StringExt::remove(s, i));
+                    check(&input, "insert/remove round trip",
+                          move |s| { s.insert(i, '€'); s.remove(i) },
+                          move |s| {
+                              $crate::StringExt::insert(s, i, '€');
+                              $crate::StringExt::remove(s, i)
+                          });
+                }
+            }
+        }
+
+        #[test]
+        fn conformance_split_off() {
+            for input in samples() {
+                for i in 0..input.len() + 2 {
+                    check(&input, "split_off", move |s| s.split_off(i),
+                          move |s| ::std::string::String::from(
+                              $crate::StringExt::as_str(&$crate::StringExt::split_off(s, i))));
+                }
+            }
+        }
+
+        #[test]
+        fn conformance_ranges() {
+            for input in samples() {
+                for i in 0..input.len() + 2 {
+                    for j in 0..input.len() + 2 {
+                        check(&input, "drain", move |s| s.drain(i..j).collect(),
+                              move |s| $crate::StringExt::drain(s, i..j)
+                                           .collect::<::std::string::String>());
+                        check(&input, "replace_range", move |s| s.replace_range(i..j, "é"),
+                              move |s| $crate::StringExt::replace_range(s, i..j, "é"));
+                        check(&input, "extend_from_within", move |s| s.extend_from_within(i..j),
+                              move |s| $crate::StringExt::extend_from_within(s, i..j));
+                    }
+                }
+                check(&input, "drain(..)", |s| s.drain(..).collect(),
+                      |s| $crate::StringExt::drain(s, ..).collect::<::std::string::String>());
+            }
+        }
+
+        #[test]
+        fn conformance_retain_remove_matches() {
+            for input in samples() {
+                check(&input, "retain", |s| s.retain(|c| c.is_ascii()),
+                      |s| $crate::StringExt::retain(s, |c| c.is_ascii()));
+                check(&input, "retain", |s| s.retain(|c| !c.is_ascii()),
+                      |s| $crate::StringExt::retain(s, |c| !c.is_ascii()));
+                for &pat in &["", "x", "xx", "é", "β", "𝄞 ", "not there"] {
+                    check(&input, "remove_matches",
+                          move |s| *s = s.replace(pat, ""),
+                          move |s| $crate::StringExt::remove_matches(s, pat));
+                }
+            }
+        }
+
+        #[test]
+        fn conformance_capacity() {
+            for input in samples() {
+                for &n in &[0, 1, 2, 10, 100] {
+                    let mut s = make(&input);
+                    $crate::StringExt::reserve(&mut s, n);
+                    assert!($crate::StringExt::capacity(&s) >= input.len() + n);
+                    $crate::StringExt::reserve_exact(&mut s, n);
+                    assert!($crate::StringExt::capacity(&s) >= input.len() + n);
+                    assert!($crate::StringExt::try_reserve(&mut s, n).is_ok());
+                    assert!($crate::StringExt::capacity(&s) >= input.len() + n);
+                    assert!($crate::StringExt::try_reserve_exact(&mut s, n).is_ok());
+                    assert!($crate::StringExt::capacity(&s) >= input.len() + n);
+                    assert_eq!($crate::StringExt::as_str(&s), input);
+
+                    $crate::StringExt::shrink_to(&mut s, n);
+                    assert!($crate::StringExt::capacity(&s) >= ::std::cmp::max(input.len(), n));
+                    $crate::StringExt::shrink_to_fit(&mut s);
+                    assert!($crate::StringExt::capacity(&s) >= input.len());
+                    assert_eq!($crate::StringExt::as_str(&s), input);
+                }
+
+                let mut s = make(&input);
+                assert!($crate::StringExt::try_reserve(&mut s, usize::MAX).is_err());
+                assert!($crate::StringExt::try_reserve_exact(&mut s, usize::MAX).is_err());
+                assert_eq!($crate::StringExt::as_str(&s), input);
+
+                // Capacity never drops below the length as a string grows.
+                let mut s = make(&input);
+                for _ in 0..40 {
+                    $crate::StringExt::push(&mut s, 'é');
+                    assert!($crate::StringExt::capacity(&s) >= $crate::StringExt::len(&s));
+                }
+            }
+        }
+
+        #[test]
+        fn conformance_accessors() {
+            for input in samples() {
+                let s = make(&input);
+                assert_eq!($crate::StringExt::len(&s), input.len());
+                assert_eq!($crate::StringExt::is_empty(&s), input.is_empty());
+                assert_eq!($crate::StringExt::as_bytes(&s), input.as_bytes());
+                assert_eq!(s, *input);
+                assert_eq!(s, input.as_str());
+                assert_eq!(s, ::std::borrow::Cow::Borrowed(input.as_str()));
+                assert_eq!(format!("{}", s), input);
+
+                let mut s = make(&input);
+                $crate::StringExt::as_mut_str(&mut s).make_ascii_uppercase();
+                assert_eq!($crate::StringExt::as_str(&s), input.to_ascii_uppercase());
+
+                assert_eq!($crate::StringExt::into_bytes(make(&input)), input.as_bytes());
+                assert_eq!(&*$crate::StringExt::into_boxed_str(make(&input)), input);
+                assert_eq!($crate::StringExt::into_chars(make(&input)).collect::<::std::string::String>(),
+                           input);
+                assert_eq!($crate::StringExt::into_chars(make(&input)).rev()
+                               .collect::<::std::string::String>(),
+                           input.chars().rev().collect::<::std::string::String>());
+            }
+        }
+    };
+}
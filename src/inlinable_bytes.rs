@@ -0,0 +1,237 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The bytes of an `InlinableString`, kept inline when the string was.
+//!
+//! `StringExt::into_bytes` has to return a `Vec<u8>`, which means allocating
+//! for an inline string. `InlinableString::into_bytes_inlined` returns an
+//! `InlinableBytes` instead: inline strings become an inline byte buffer, and
+//! heap strings hand over their existing `Vec<u8>` without copying.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::InlinableString;
+//!
+//! let bytes = InlinableString::from("small").into_bytes_inlined();
+//! assert!(bytes.is_inline());
+//! assert_eq!(&bytes[..], b"small");
+//! ```
+
+use inline_string::{INLINE_STRING_CAPACITY, InlineString};
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops;
+
+/// An owned byte buffer that stores up to `INLINE_STRING_CAPACITY` bytes
+/// inline, or a heap-allocated `Vec<u8>`.
+#[derive(Clone)]
+pub struct InlinableBytes {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Heap(Vec<u8>),
+    // Invariant: `len <= INLINE_STRING_CAPACITY`.
+    Inline {
+        bytes: [u8; INLINE_STRING_CAPACITY],
+        len: u8,
+    },
+}
+
+impl InlinableBytes {
+    /// Returns true if the bytes are stored inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// assert!(InlinableString::from("small").into_bytes_inlined().is_inline());
+    /// ```
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        match self.repr {
+            Repr::Heap(_) => false,
+            Repr::Inline { .. } => true,
+        }
+    }
+
+    /// Returns true if the bytes are stored in a heap-allocated `Vec<u8>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let long = "a string that is too long to be stored inline";
+    /// assert!(InlinableString::from(long).into_bytes_inlined().is_heap());
+    /// ```
+    #[inline]
+    pub fn is_heap(&self) -> bool {
+        !self.is_inline()
+    }
+
+    /// Returns the bytes as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        match self.repr {
+            Repr::Heap(ref v) => v,
+            Repr::Inline { ref bytes, len } => &bytes[..len as usize],
+        }
+    }
+
+    /// Converts the bytes into a `Vec<u8>`. A heap buffer is moved out
+    /// without copying; an inline buffer is copied into a new `Vec<u8>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let v = InlinableString::from("hello").into_bytes_inlined().into_vec();
+    /// assert_eq!(v, b"hello");
+    /// ```
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.repr {
+            Repr::Heap(v) => v,
+            Repr::Inline { bytes, len } => bytes[..len as usize].to_vec(),
+        }
+    }
+}
+
+impl<'a> From<&'a InlineString> for InlinableBytes {
+    #[inline]
+    fn from(s: &'a InlineString) -> InlinableBytes {
+        let mut bytes = [0; INLINE_STRING_CAPACITY];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        InlinableBytes {
+            repr: Repr::Inline {
+                bytes,
+                len: s.len() as u8,
+            },
+        }
+    }
+}
+
+impl From<Vec<u8>> for InlinableBytes {
+    #[inline]
+    fn from(v: Vec<u8>) -> InlinableBytes {
+        InlinableBytes { repr: Repr::Heap(v) }
+    }
+}
+
+impl From<InlinableBytes> for Vec<u8> {
+    #[inline]
+    fn from(bytes: InlinableBytes) -> Vec<u8> {
+        bytes.into_vec()
+    }
+}
+
+impl fmt::Debug for InlinableBytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl ops::Deref for InlinableBytes {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for InlinableBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl Borrow<[u8]> for InlinableBytes {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl PartialEq for InlinableBytes {
+    #[inline]
+    fn eq(&self, other: &InlinableBytes) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for InlinableBytes {}
+
+impl PartialEq<[u8]> for InlinableBytes {
+    #[inline]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<'a> PartialEq<&'a [u8]> for InlinableBytes {
+    #[inline]
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl PartialEq<Vec<u8>> for InlinableBytes {
+    #[inline]
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == &other[..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InlinableBytes;
+    use inline_string::{INLINE_STRING_CAPACITY, InlineString};
+
+    #[test]
+    fn test_from_inline_string() {
+        let s = InlineString::from("hello");
+        let bytes = InlinableBytes::from(&s);
+        assert!(bytes.is_inline());
+        assert_eq!(bytes, &b"hello"[..]);
+        assert_eq!(bytes.len(), 5);
+
+        let full = "a".repeat(INLINE_STRING_CAPACITY);
+        let bytes = InlinableBytes::from(&InlineString::from(&full[..]));
+        assert!(bytes.is_inline());
+        assert_eq!(bytes, full.as_bytes());
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let v = b"a buffer that is too long to be stored inline".to_vec();
+        let ptr = v.as_ptr();
+        let bytes = InlinableBytes::from(v);
+        assert!(bytes.is_heap());
+        let v = bytes.into_vec();
+        assert_eq!(v.as_ptr(), ptr);
+
+        let bytes = InlinableBytes::from(&InlineString::from("abc"));
+        assert_eq!(Vec::from(bytes), b"abc".to_vec());
+    }
+
+    #[test]
+    fn test_eq_ignores_storage() {
+        let inline = InlinableBytes::from(&InlineString::from("abc"));
+        let heap = InlinableBytes::from(b"abc".to_vec());
+        assert_eq!(inline, heap);
+        assert_eq!(heap, b"abc".to_vec());
+        assert_eq!(format!("{:?}", inline), format!("{:?}", heap));
+    }
+}
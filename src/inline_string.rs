@@ -21,6 +21,11 @@
 //! work around potential sources of panics (eg, in the `From` trait
 //! implementation).
 //!
+//! With the `inline-string-ext` cargo feature enabled, `InlineString` does
+//! implement `StringExt`, for generic code that knows its strings stay short.
+//! Any operation that would grow the string past `INLINE_STRING_CAPACITY`
+//! panics, and fallible methods such as `try_reserve` return an error instead.
+//!
 //! # Examples
 //!
 //! ```
@@ -39,10 +44,16 @@ use std::fmt;
 use std::hash;
 use std::io::Write;
 use std::mem;
-use std::ops;
+use std::ops::{self, RangeBounds};
 use std::ptr;
 use std::str;
 
+use string_ext::char_range;
+#[cfg(feature = "inline-string-ext")]
+use string_ext::{capacity_overflow, StringExt};
+#[cfg(feature = "inline-string-ext")]
+use std::collections::TryReserveError;
+
 /// The capacity (in bytes) of inline storage for small strings.
 /// `InlineString::len()` may never be larger than this.
 ///
@@ -140,6 +151,18 @@ impl fmt::Write for InlineString {
     }
 }
 
+// Needed as a supertrait of `StringExt`. Implementing `Borrow<str>` commits
+// `InlineString` to hashing and comparing exactly like the `str` it borrows
+// as, which its `Hash` and `PartialEq` impls already do by forwarding to
+// `str`; keep them that way.
+#[cfg(feature = "inline-string-ext")]
+impl borrow::Borrow<str> for InlineString {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
 impl hash::Hash for InlineString {
     #[inline]
     fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
@@ -189,6 +212,26 @@ impl ops::Index<ops::RangeFull> for InlineString {
     }
 }
 
+impl ops::Index<ops::RangeInclusive<usize>> for InlineString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeInclusive<usize>) -> &str {
+        self.assert_sanity();
+        &self[..][index]
+    }
+}
+
+impl ops::Index<ops::RangeToInclusive<usize>> for InlineString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeToInclusive<usize>) -> &str {
+        self.assert_sanity();
+        &self[..][index]
+    }
+}
+
 impl ops::IndexMut<ops::Range<usize>> for InlineString {
     #[inline]
     fn index_mut(&mut self, index: ops::Range<usize>) -> &mut str {
@@ -224,6 +267,22 @@ impl ops::IndexMut<ops::RangeFull> for InlineString {
     }
 }
 
+impl ops::IndexMut<ops::RangeInclusive<usize>> for InlineString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeInclusive<usize>) -> &mut str {
+        self.assert_sanity();
+        &mut self[..][index]
+    }
+}
+
+impl ops::IndexMut<ops::RangeToInclusive<usize>> for InlineString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeToInclusive<usize>) -> &mut str {
+        self.assert_sanity();
+        &mut self[..][index]
+    }
+}
+
 impl ops::Deref for InlineString {
     type Target = str;
 
@@ -291,7 +350,9 @@ macro_rules! impl_eq {
 
 impl_eq! { InlineString, str }
 impl_eq! { InlineString, &'a str }
+impl_eq! { InlineString, String }
 impl_eq! { borrow::Cow<'a, str>, InlineString }
+impl_eq! { InlineString, Box<str> }
 
 impl InlineString {
     #[cfg_attr(feature = "nightly", allow(inline_always))]
@@ -446,7 +507,7 @@ impl InlineString {
     pub fn truncate(&mut self, new_len: usize) {
         self.assert_sanity();
 
-        assert!(self.char_indices().filter(|&(i, _)| i == new_len).next().is_some(),
+        assert!(self.is_char_boundary(new_len),
                 "inlinable_string::InlineString::truncate: new_len is not a character
                  boundary");
         assert!(new_len <= self.len());
@@ -455,6 +516,59 @@ impl InlineString {
         self.assert_sanity();
     }
 
+    /// Resizes the string to `new_len` bytes, either truncating it or
+    /// appending copies of `ch`.
+    ///
+    /// Returns `NotEnoughSpaceError` and leaves the string unchanged if
+    /// `new_len` is greater than `INLINE_STRING_CAPACITY`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if shrinking and `new_len` is not a character boundary, or if
+    /// growing and the number of bytes to add is not a multiple of
+    /// `ch.len_utf8()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("42");
+    /// s.resize(5, '.').unwrap();
+    /// assert_eq!(s, "42...");
+    /// s.resize(1, '.').unwrap();
+    /// assert_eq!(s, "4");
+    /// ```
+    #[inline]
+    pub fn resize(&mut self, new_len: usize, ch: char) -> Result<(), NotEnoughSpaceError> {
+        self.assert_sanity();
+
+        if new_len <= self.len() {
+            self.truncate(new_len);
+            return Ok(());
+        }
+
+        let additional = new_len - self.len();
+        let count = additional / ch.len_utf8();
+        assert!(count * ch.len_utf8() == additional,
+                "inlinable_string::InlineString::resize: new_len cannot be reached by
+                 appending whole characters");
+        if new_len > INLINE_STRING_CAPACITY {
+            return Err(NotEnoughSpaceError);
+        }
+
+        let mut buf = [0; 4];
+        let encoded = ch.encode_utf8(&mut buf);
+        for _ in 0..count {
+            self.push_str(encoded)
+                .expect("inlinable_string: internal error: should have enough space, we
+                         checked above");
+        }
+
+        self.assert_sanity();
+        Ok(())
+    }
+
     /// Removes the last character from the string buffer and returns it.
     /// Returns `None` if this string buffer is empty.
     ///
@@ -569,6 +683,200 @@ impl InlineString {
         Ok(())
     }
 
+    /// Inserts a string into the string buffer at byte position `idx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("bar");
+    /// s.insert_str(0, "foo");
+    /// assert!(s == "foobar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `idx` does not lie on a character boundary or is out of bounds, then
+    /// this function will panic.
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, string: &str) -> Result<(), NotEnoughSpaceError> {
+        self.assert_sanity();
+        assert!(self.is_char_boundary(idx));
+
+        let string_len = string.len();
+        let new_length = self.len() + string_len;
+
+        if new_length > INLINE_STRING_CAPACITY {
+            return Err(NotEnoughSpaceError);
+        }
+
+        unsafe {
+            ptr::copy(self.bytes.as_ptr().add(idx),
+                      self.bytes.as_mut_ptr().add(idx + string_len),
+                      self.len() - idx);
+            ptr::copy_nonoverlapping(string.as_ptr(),
+                                     self.bytes.as_mut_ptr().add(idx),
+                                     string_len);
+        }
+        self.length = new_length as u8;
+
+        self.assert_sanity();
+        Ok(())
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Returns a new string containing bytes `[at, len)`, while `self` is left
+    /// containing bytes `[0, at)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("foobar");
+    /// let t = s.split_off(3);
+    /// assert_eq!(s, "foo");
+    /// assert_eq!(t, "bar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `at` does not lie on a character boundary or is out of bounds, then
+    /// this function will panic.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> InlineString {
+        self.assert_sanity();
+        assert!(self.is_char_boundary(at));
+
+        let other = InlineString::from(&self[at..]);
+        self.length = at as u8;
+
+        self.assert_sanity();
+        other
+    }
+
+    /// Retains only the characters specified by the predicate, removing the
+    /// rest in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("f_o_ob_ar");
+    /// s.retain(|c| c != '_');
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        self.assert_sanity();
+
+        let len = self.len();
+        let mut idx = 0;
+        let mut deleted = 0;
+
+        // Hide the contents while compacting, so that if `f` panics we are
+        // left empty rather than with a half-shifted buffer.
+        self.length = 0;
+
+        while idx < len {
+            let ch = unsafe { str::from_utf8_unchecked(&self.bytes[idx..len]) }
+                .chars()
+                .next()
+                .expect("inlinable_string: internal error: idx should be in bounds");
+            let char_len = ch.len_utf8();
+
+            if !f(ch) {
+                deleted += char_len;
+            } else if deleted > 0 {
+                self.bytes.copy_within(idx..idx + char_len, idx - deleted);
+            }
+            idx += char_len;
+        }
+
+        self.length = (len - deleted) as u8;
+        self.assert_sanity();
+    }
+
+    /// Removes the specified range in the string buffer, and replaces it with
+    /// the given string. The given string doesn't need to be the same length
+    /// as the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("foobar");
+    /// s.replace_range(1..3, "ubar ");
+    /// assert_eq!(s, "fubar bar");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the start or end of the range does not lie on a character boundary,
+    /// or if the range is out of bounds, then this function will panic.
+    #[inline]
+    pub fn replace_range<R>(&mut self, range: R, replace_with: &str) -> Result<(), NotEnoughSpaceError>
+        where R: RangeBounds<usize>
+    {
+        self.assert_sanity();
+
+        let (start, end) = char_range(self, range);
+        let len = self.len();
+        let new_length = len - (end - start) + replace_with.len();
+
+        if new_length > INLINE_STRING_CAPACITY {
+            return Err(NotEnoughSpaceError);
+        }
+
+        self.bytes.copy_within(end..len, start + replace_with.len());
+        self.bytes[start..start + replace_with.len()].copy_from_slice(replace_with.as_bytes());
+        self.length = new_length as u8;
+
+        self.assert_sanity();
+        Ok(())
+    }
+
+    /// Copies the bytes in range `src` of the string buffer to its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("abcde");
+    /// s.extend_from_within(2..);
+    /// assert_eq!(s, "abcdecde");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the start or end of the range does not lie on a character boundary,
+    /// or if the range is out of bounds, then this function will panic.
+    #[inline]
+    pub fn extend_from_within<R>(&mut self, src: R) -> Result<(), NotEnoughSpaceError>
+        where R: RangeBounds<usize>
+    {
+        self.assert_sanity();
+
+        let (start, end) = char_range(self, src);
+        let len = self.len();
+        let new_length = len + (end - start);
+
+        if new_length > INLINE_STRING_CAPACITY {
+            return Err(NotEnoughSpaceError);
+        }
+
+        self.bytes.copy_within(start..end, len);
+        self.length = new_length as u8;
+
+        self.assert_sanity();
+        Ok(())
+    }
+
     /// Views the internal string buffer as a mutable sequence of bytes.
     ///
     /// This is unsafe because it does not check to ensure that the resulting
@@ -646,6 +954,123 @@ impl InlineString {
     }
 }
 
+#[cfg(feature = "inline-string-ext")]
+impl StringExt for InlineString {
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity <= INLINE_STRING_CAPACITY,
+                "inlinable_string::InlineString::with_capacity: capacity exceeds \
+                 INLINE_STRING_CAPACITY");
+        InlineString::new()
+    }
+
+    #[inline]
+    fn into_bytes(self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    #[inline]
+    fn push_str(&mut self, string: &str) {
+        InlineString::push_str(self, string)
+            .expect("inlinable_string::InlineString::push_str: exceeds INLINE_STRING_CAPACITY")
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        INLINE_STRING_CAPACITY
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        assert!(self.len().saturating_add(additional) <= INLINE_STRING_CAPACITY,
+                "inlinable_string::InlineString::reserve: exceeds INLINE_STRING_CAPACITY");
+    }
+
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if self.len().saturating_add(additional) <= INLINE_STRING_CAPACITY {
+            Ok(())
+        } else {
+            Err(capacity_overflow())
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, ch: char) {
+        InlineString::push(self, ch)
+            .expect("inlinable_string::InlineString::push: exceeds INLINE_STRING_CAPACITY")
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        InlineString::as_bytes(self)
+    }
+
+    #[inline]
+    fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            InlineString::truncate(self, new_len)
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<char> {
+        InlineString::pop(self)
+    }
+
+    #[inline]
+    fn remove(&mut self, idx: usize) -> char {
+        InlineString::remove(self, idx)
+    }
+
+    #[inline]
+    fn insert(&mut self, idx: usize, ch: char) {
+        InlineString::insert(self, idx, ch)
+            .expect("inlinable_string::InlineString::insert: exceeds INLINE_STRING_CAPACITY")
+    }
+
+    #[inline]
+    fn insert_str(&mut self, idx: usize, string: &str) {
+        InlineString::insert_str(self, idx, string)
+            .expect("inlinable_string::InlineString::insert_str: exceeds INLINE_STRING_CAPACITY")
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        InlineString::split_off(self, at)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F) where F: FnMut(char) -> bool {
+        InlineString::retain(self, f)
+    }
+
+    #[inline]
+    fn replace_range<R>(&mut self, range: R, replace_with: &str) where R: RangeBounds<usize> {
+        InlineString::replace_range(self, range, replace_with)
+            .expect("inlinable_string::InlineString::replace_range: exceeds \
+                     INLINE_STRING_CAPACITY")
+    }
+
+    #[inline]
+    fn extend_from_within<R>(&mut self, src: R) where R: RangeBounds<usize> {
+        InlineString::extend_from_within(self, src)
+            .expect("inlinable_string::InlineString::extend_from_within: exceeds \
+                     INLINE_STRING_CAPACITY")
+    }
+
+    #[inline]
+    unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        let length = self.len();
+        &mut self.bytes[0..length]
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        InlineString::len(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{InlineString, NotEnoughSpaceError, INLINE_STRING_CAPACITY};
@@ -684,6 +1109,120 @@ mod tests {
         assert_eq!(s.insert(0, 'a'), Err(NotEnoughSpaceError));
     }
 
+    #[test]
+    fn test_insert_str() {
+        let mut s = InlineString::from("ac");
+        assert!(s.insert_str(1, "b").is_ok());
+        assert_eq!(s, "abc");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        assert_eq!(s.insert_str(1, long_str), Err(NotEnoughSpaceError));
+        assert_eq!(s, "abc");
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut s = InlineString::from("a𝄞bé");
+        s.retain(|c| c.is_ascii());
+        assert_eq!(s, "ab");
+    }
+
+    #[test]
+    fn test_replace_range() {
+        let mut s = InlineString::from("foobar");
+        assert!(s.replace_range(3.., "").is_ok());
+        assert_eq!(s, "foo");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        assert_eq!(s.replace_range(..1, long_str), Err(NotEnoughSpaceError));
+        assert_eq!(s, "foo");
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut s = InlineString::from("abc");
+        assert!(s.extend_from_within(..).is_ok());
+        assert_eq!(s, "abcabc");
+
+        for _ in 0..2 {
+            assert!(s.extend_from_within(..).is_ok());
+        }
+        assert_eq!(s.extend_from_within(..), Err(NotEnoughSpaceError));
+        assert_eq!(s.len(), 24);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut s = InlineString::from("héllo");
+        s.truncate(6);
+        assert_eq!(s, "héllo");
+        s.truncate(3);
+        assert_eq!(s, "hé");
+        s.truncate(0);
+        assert_eq!(s, "");
+        s.truncate(0);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_truncate_to_len() {
+        let mut s = InlineString::from("hello");
+        let len = s.len();
+        s.truncate(len);
+        assert_eq!(s, "hello");
+
+        let mut s = InlineString::from("");
+        s.truncate(0);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    #[should_panic(expected = "new_len is not a character")]
+    fn test_truncate_inside_char() {
+        let mut s = InlineString::from("hé");
+        s.truncate(2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_truncate_past_end() {
+        let mut s = InlineString::from("hello");
+        s.truncate(6);
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut s = InlineString::from("ab");
+        assert!(s.resize(INLINE_STRING_CAPACITY, '-').is_ok());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.starts_with("ab--"));
+
+        assert_eq!(s.resize(INLINE_STRING_CAPACITY + 1, '-'), Err(NotEnoughSpaceError));
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+
+        let mut s = InlineString::from("hé");
+        assert!(s.resize(7, 'é').is_ok());
+        assert_eq!(s, "hééé");
+        assert!(s.resize(3, 'x').is_ok());
+        assert_eq!(s, "hé");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_resize_inside_char() {
+        let mut s = InlineString::from("hé");
+        let _ = s.resize(2, 'x');
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_resize_partial_char() {
+        let mut s = InlineString::from("h");
+        let _ = s.resize(4, 'é');
+    }
+
     #[test]
     fn test_write() {
         use fmt::{Error, Write};
@@ -699,6 +1238,128 @@ mod tests {
         assert_eq!(write!(&mut s, "a"), Err(Error));
         assert_eq!(&normal_string[..], &s[..]);
     }
+
+    #[test]
+    fn test_string_ext_feature_gate() {
+        use std::marker::PhantomData;
+        use string_ext::StringExt;
+
+        // Autoref-based probe: the inherent method is only applicable when
+        // `T: StringExt`, otherwise the blanket trait method is used.
+        struct Probe<T>(PhantomData<T>);
+        #[allow(dead_code)]
+        trait Fallback {
+            fn implements_string_ext(&self) -> bool { false }
+        }
+        impl<T> Fallback for Probe<T> {}
+        impl<T: StringExt> Probe<T> {
+            fn implements_string_ext(&self) -> bool { true }
+        }
+
+        assert!(Probe::<String>(PhantomData).implements_string_ext());
+        assert_eq!(Probe::<InlineString>(PhantomData).implements_string_ext(),
+                   cfg!(feature = "inline-string-ext"));
+    }
+
+    #[test]
+    fn test_index_inclusive() {
+        let s = InlineString::from("héllo");
+        let last = s.len() - 1;
+        assert_eq!(&s[..=last], "héllo");
+        assert_eq!(&s[3..=last], "llo");
+        assert_eq!(&s[1..=2], "é");
+        assert_eq!(&s[..=0], "h");
+
+        let mut s = InlineString::from("héllo");
+        s[3..=4].make_ascii_uppercase();
+        s[..=0].make_ascii_uppercase();
+        assert_eq!(s, "HéLLo");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a char boundary")]
+    fn test_index_inclusive_inside_char() {
+        let s = InlineString::from("héllo");
+        let _ = &s[..=1];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_inclusive_past_end() {
+        let s = InlineString::from("hello");
+        let _ = &s[..=5];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_inclusive_overflow() {
+        let s = InlineString::from("hello");
+        let _ = &s[..=usize::MAX];
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "inline-string-ext")]
+mod string_ext_tests {
+    use super::{InlineString, INLINE_STRING_CAPACITY};
+    use string_ext::StringExt;
+
+    fn shout<S: StringExt>(string: &mut S) {
+        string.as_mut_str().make_ascii_uppercase();
+        string.insert(0, '<');
+        string.push('>');
+    }
+
+    #[test]
+    fn test_generic() {
+        let mut s = InlineString::from("hi");
+        shout(&mut s);
+        assert_eq!(s, "<HI>");
+
+        let mut s = <InlineString as StringExt>::from_utf8(b"caf\xC3\xA9".to_vec()).unwrap();
+        StringExt::retain(&mut s, |c| c != 'a');
+        StringExt::truncate(&mut s, 10);
+        assert_eq!(s, "cfé");
+        assert_eq!(StringExt::into_bytes(s), "cfé".as_bytes());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut s = <InlineString as StringExt>::with_capacity(10);
+        assert_eq!(StringExt::capacity(&s), INLINE_STRING_CAPACITY);
+        StringExt::reserve(&mut s, INLINE_STRING_CAPACITY);
+        assert!(StringExt::try_reserve(&mut s, INLINE_STRING_CAPACITY).is_ok());
+        assert!(StringExt::try_reserve(&mut s, INLINE_STRING_CAPACITY + 1).is_err());
+        assert!(<InlineString as StringExt>::try_with_capacity(INLINE_STRING_CAPACITY + 1)
+                .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineString::push_str: exceeds INLINE_STRING_CAPACITY")]
+    fn test_push_str_overflow() {
+        let mut s = InlineString::new();
+        StringExt::push_str(&mut s, &"a".repeat(INLINE_STRING_CAPACITY + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineString::push: exceeds INLINE_STRING_CAPACITY")]
+    fn test_push_overflow() {
+        let mut s = InlineString::from("a".repeat(INLINE_STRING_CAPACITY).as_str());
+        StringExt::push(&mut s, 'a');
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineString::reserve: exceeds INLINE_STRING_CAPACITY")]
+    fn test_reserve_overflow() {
+        let mut s = InlineString::from("a");
+        StringExt::reserve(&mut s, INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineString::with_capacity: capacity exceeds")]
+    fn test_with_capacity_overflow() {
+        <InlineString as StringExt>::with_capacity(INLINE_STRING_CAPACITY + 1);
+    }
 }
 
 #[cfg(test)]
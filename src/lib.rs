@@ -16,9 +16,10 @@
 //!
 //! `StringExt`'s API is mostly identical to `std::string::String`; unstable and
 //! deprecated methods are not included. A `StringExt` implementation is
-//! provided for both `std::string::String` and `InlinableString`. This enables
+//! provided for both `std::string::String` and `InlinableString`, as well as
+//! for `Cow<str>`, which only clones when it is first mutated. This enables
 //! `InlinableString` to generally work as a drop-in replacement for
-//! `std::string::String` and `&StringExt` to work with references to either
+//! `std::string::String` and `&dyn StringExt` to work with references to either
 //! type.
 //!
 //! # Examples
@@ -28,16 +29,16 @@
 //!
 //! // Small strings are stored inline and don't perform heap-allocation.
 //! let mut s = InlinableString::from("small");
-//! assert_eq!(s.capacity(), inlinable_string::INLINE_STRING_CAPACITY);
+//! assert!(s.is_inline());
 //!
 //! // Inline strings are transparently promoted to heap-allocated strings when
 //! // they grow too big.
 //! s.push_str("a really long string that's bigger than `INLINE_STRING_CAPACITY`");
-//! assert!(s.capacity() > inlinable_string::INLINE_STRING_CAPACITY);
+//! assert!(s.is_heap());
 //!
 //! // This method can work on strings potentially stored inline on the stack,
 //! // on the heap, or plain old `std::string::String`s!
-//! fn takes_a_string_reference(string: &mut StringExt) {
+//! fn takes_a_string_reference(string: &mut dyn StringExt) {
 //!    // Do something with the string...
 //!    string.push_str("it works!");
 //! }
@@ -55,7 +56,7 @@
 //! * If `my_string` is always on the stack: `let my_string = String::new();` →
 //! `let my_string = InlinableString::new();`
 //!
-//! * `fn foo(string: &mut String) { ... }` → `fn foo(string: &mut StringExt) { ... }`
+//! * `fn foo(string: &mut String) { ... }` → `fn foo(string: &mut dyn StringExt) { ... }`
 //!
 //! * `fn foo(string: &str) { ... }` does not need to be modified.
 //!
@@ -72,6 +73,17 @@
 //! Add the `serde` feature to your `Cargo.toml` to enable serialization.
 //! 
 //! [serde-docs]: https://serde.rs
+//!
+//! # SIMD UTF-8 validation
+//!
+//! Add the `simd-utf8` feature to validate input to `from_utf8`,
+//! `from_utf8_slice`, `from_utf8_lossy_inlined` and `from_utf8_lossy_owned`
+//! with [`simdutf8`][simdutf8-docs], which is considerably faster than the
+//! standard library on long input. Invalid input is re-checked with the standard library
+//! to build the error, so the errors returned, including
+//! `Utf8Error::valid_up_to`, are the same with or without the feature.
+//!
+//! [simdutf8-docs]: https://docs.rs/simdutf8
 
 #![forbid(missing_docs)]
 
@@ -87,33 +99,55 @@ extern crate serde;
 #[cfg(all(test, feature = "serde"))]
 extern crate serde_test;
 
+#[cfg(feature = "simd-utf8")]
+extern crate simdutf8;
+
 #[cfg(test)]
 #[cfg(feature = "nightly")]
 extern crate test;
 
+#[macro_use]
+mod conformance;
+
 #[cfg(feature = "serde")]
 mod serde_impl;
 
+pub mod inlinable_bytes;
 pub mod inline_string;
 pub mod string_ext;
 
+pub use inlinable_bytes::InlinableBytes;
 pub use inline_string::{INLINE_STRING_CAPACITY, InlineString};
-pub use string_ext::StringExt;
+pub use string_ext::{StringExt, StringExtWrite};
 
-use std::borrow::{Borrow, Cow};
-use std::cmp::Ordering;
+use std::borrow::{Borrow, BorrowMut, Cow};
+use std::cmp::{self, Ordering};
+use std::collections::TryReserveError;
+use std::convert::TryFrom;
+use std::error;
+use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::hash;
 use std::iter;
+use std::io;
 use std::mem;
-use std::ops;
-use std::string::{FromUtf8Error, FromUtf16Error};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::ops::{self, RangeBounds};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str;
+use std::string::FromUtf8Error;
+use std::sync::Arc;
+use std::vec;
+
+use inline_string::NotEnoughSpaceError;
+use string_ext::char_range;
 
 /// An owned, grow-able UTF-8 string that allocates short strings inline on the
 /// stack.
 ///
 /// See the [module level documentation](./index.html) for more.
-#[derive(Clone, Eq)]
+#[derive(Eq)]
 pub enum InlinableString {
     /// A heap-allocated string.
     Heap(String),
@@ -121,6 +155,50 @@ pub enum InlinableString {
     Inline(InlineString),
 }
 
+/// The error returned by `InlinableString::from_ascii` when the input contains
+/// a byte that is not ASCII.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotAsciiError {
+    index: usize,
+}
+
+impl NotAsciiError {
+    /// Returns the index of the first byte in the input that is not ASCII.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for NotAsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "non-ASCII byte at index {}", self.index)
+    }
+}
+
+impl error::Error for NotAsciiError {}
+
+impl Clone for InlinableString {
+    /// Clones the string, storing the clone inline whenever it fits, even if
+    /// `self` is on the heap.
+    fn clone(&self) -> InlinableString {
+        match *self {
+            InlinableString::Heap(ref s) if s.len() <= INLINE_STRING_CAPACITY => {
+                InlinableString::Inline(InlineString::from(&s[..]))
+            },
+            InlinableString::Heap(ref s) => InlinableString::Heap(s.clone()),
+            InlinableString::Inline(ref s) => InlinableString::Inline(s.clone()),
+        }
+    }
+
+    /// Copies `source` into `self`, reusing `self`'s heap buffer when it is
+    /// already large enough rather than allocating a new one.
+    #[inline]
+    fn clone_from(&mut self, source: &InlinableString) {
+        self.assign(source);
+    }
+}
+
 impl fmt::Debug for InlinableString {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(&self as &str, f)
@@ -143,11 +221,55 @@ impl<'a> iter::FromIterator<&'a str> for InlinableString {
     }
 }
 
+impl iter::FromIterator<String> for InlinableString {
+    fn from_iter<I: IntoIterator<Item=String>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        push_strs(&mut buf, iter);
+        buf
+    }
+}
+
+impl<'a> iter::FromIterator<Cow<'a, str>> for InlinableString {
+    fn from_iter<I: IntoIterator<Item=Cow<'a, str>>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl iter::FromIterator<Box<str>> for InlinableString {
+    fn from_iter<I: IntoIterator<Item=Box<str>>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl iter::FromIterator<InlinableString> for InlinableString {
+    fn from_iter<I: IntoIterator<Item=InlinableString>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl<'a> iter::FromIterator<&'a InlinableString> for InlinableString {
+    fn from_iter<I: IntoIterator<Item=&'a InlinableString>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
 impl Extend<char> for InlinableString {
     fn extend<I: IntoIterator<Item=char>>(&mut self, iterable: I) {
         let iterator = iterable.into_iter();
-        let (lower_bound, _) = iterator.size_hint();
-        self.reserve(lower_bound);
+        // The size hint is only advisory, so don't let it promote an inline
+        // string; stay inline until a push actually overflows.
+        if let InlinableString::Heap(ref mut s) = *self {
+            let (lower_bound, _) = iterator.size_hint();
+            s.reserve(lower_bound);
+        }
         for ch in iterator {
             self.push(ch);
         }
@@ -162,12 +284,37 @@ impl<'a> Extend<&'a char> for InlinableString {
 
 impl<'a> Extend<&'a str> for InlinableString {
     fn extend<I: IntoIterator<Item=&'a str>>(&mut self, iterable: I) {
-        let iterator = iterable.into_iter();
-        let (lower_bound, _) = iterator.size_hint();
-        self.reserve(lower_bound);
-        for s in iterator {
-            self.push_str(s);
-        }
+        push_strs(self, iterable);
+    }
+}
+
+impl Extend<String> for InlinableString {
+    fn extend<I: IntoIterator<Item=String>>(&mut self, iterable: I) {
+        push_strs(self, iterable);
+    }
+}
+
+impl<'a> Extend<Cow<'a, str>> for InlinableString {
+    fn extend<I: IntoIterator<Item=Cow<'a, str>>>(&mut self, iterable: I) {
+        push_strs(self, iterable);
+    }
+}
+
+impl Extend<Box<str>> for InlinableString {
+    fn extend<I: IntoIterator<Item=Box<str>>>(&mut self, iterable: I) {
+        push_strs(self, iterable);
+    }
+}
+
+impl Extend<InlinableString> for InlinableString {
+    fn extend<I: IntoIterator<Item=InlinableString>>(&mut self, iterable: I) {
+        push_strs(self, iterable);
+    }
+}
+
+impl<'a> Extend<&'a InlinableString> for InlinableString {
+    fn extend<I: IntoIterator<Item=&'a InlinableString>>(&mut self, iterable: I) {
+        push_strs(self, iterable);
     }
 }
 
@@ -175,12 +322,69 @@ impl<'a> ops::Add<&'a str> for InlinableString {
     type Output = InlinableString;
 
     #[inline]
-    fn add(mut self, other: &str) -> InlinableString {
-        self.push_str(other);
+    fn add(self, other: &str) -> InlinableString {
+        add_str(self, other)
+    }
+}
+
+impl ops::Add<InlinableString> for InlinableString {
+    type Output = InlinableString;
+
+    #[inline]
+    fn add(self, other: InlinableString) -> InlinableString {
+        add_str(self, &other)
+    }
+}
+
+impl<'a> ops::Add<&'a InlinableString> for InlinableString {
+    type Output = InlinableString;
+
+    #[inline]
+    fn add(self, other: &'a InlinableString) -> InlinableString {
+        add_str(self, other)
+    }
+}
+
+impl<'a> ops::Add<&'a InlineString> for InlinableString {
+    type Output = InlinableString;
+
+    #[inline]
+    fn add(self, other: &'a InlineString) -> InlinableString {
+        add_str(self, other)
+    }
+}
+
+impl ops::Add<char> for InlinableString {
+    type Output = InlinableString;
+
+    #[inline]
+    fn add(mut self, ch: char) -> InlinableString {
+        self.push(ch);
         self
     }
 }
 
+impl<'a> ops::AddAssign<&'a str> for InlinableString {
+    #[inline]
+    fn add_assign(&mut self, other: &'a str) {
+        self.push_str(other);
+    }
+}
+
+impl<'a> ops::AddAssign<&'a String> for InlinableString {
+    #[inline]
+    fn add_assign(&mut self, other: &'a String) {
+        self.push_str(other);
+    }
+}
+
+impl ops::AddAssign<char> for InlinableString {
+    #[inline]
+    fn add_assign(&mut self, ch: char) {
+        self.push(ch);
+    }
+}
+
 impl PartialOrd<InlinableString> for InlinableString {
     fn partial_cmp(&self, rhs: &InlinableString) -> Option<Ordering> {
         Some(Ord::cmp(&self[..], &rhs[..]))
@@ -194,6 +398,28 @@ impl Ord for InlinableString {
     }
 }
 
+macro_rules! impl_ord {
+    ($lhs:ty, $rhs: ty) => {
+        impl<'a> PartialOrd<$rhs> for $lhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$rhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+
+        impl<'a> PartialOrd<$lhs> for $rhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$lhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+    }
+}
+
+impl_ord! { InlinableString, str }
+impl_ord! { InlinableString, &'a str }
+impl_ord! { InlinableString, String }
+
 impl hash::Hash for InlinableString {
     #[inline]
     fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
@@ -207,6 +433,12 @@ impl Borrow<str> for InlinableString {
     }
 }
 
+impl BorrowMut<str> for InlinableString {
+    fn borrow_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
 impl AsRef<str> for InlinableString {
     fn as_ref(&self) -> &str {
         match *self {
@@ -216,6 +448,20 @@ impl AsRef<str> for InlinableString {
     }
 }
 
+impl AsRef<OsStr> for InlinableString {
+    #[inline]
+    fn as_ref(&self) -> &OsStr {
+        OsStr::new(&self[..])
+    }
+}
+
+impl AsRef<Path> for InlinableString {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        Path::new(&self[..])
+    }
+}
+
 impl AsMut<str> for InlinableString {
     fn as_mut(&mut self) -> &mut str {
         match *self {
@@ -236,6 +482,12 @@ impl<'a> From<&'a str> for InlinableString {
     }
 }
 
+/// Converts a `String` without copying when it is longer than
+/// `INLINE_STRING_CAPACITY`: its buffer becomes the `Heap` variant as is.
+///
+/// A string that fits inline is copied into the `Inline` variant and its
+/// allocation is dropped, even if it had spare capacity. To keep the buffer
+/// instead, use `InlinableString::from_string_buffer`.
 impl From<String> for InlinableString {
     #[inline]
     fn from(string: String) -> InlinableString {
@@ -247,531 +499,5261 @@ impl From<String> for InlinableString {
     }
 }
 
-impl Default for InlinableString {
-    fn default() -> Self {
-        InlinableString::new()
+impl<'a> From<&'a mut str> for InlinableString {
+    #[inline]
+    fn from(string: &'a mut str) -> InlinableString {
+        InlinableString::from(&*string)
     }
 }
 
-impl fmt::Display for InlinableString {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        match *self {
-            InlinableString::Heap(ref s) => s.fmt(f),
-            InlinableString::Inline(ref s) => s.fmt(f),
-        }
+impl<'a> From<&'a String> for InlinableString {
+    #[inline]
+    fn from(string: &'a String) -> InlinableString {
+        InlinableString::from(string.as_str())
     }
 }
 
-impl fmt::Write for InlinableString {
-    fn write_char(&mut self, ch: char) -> Result<(), fmt::Error> {
-        self.push(ch);
-        Ok(())
-    }
-    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
-        self.push_str(s);
-        Ok(())
+impl<'a> From<Cow<'a, str>> for InlinableString {
+    #[inline]
+    fn from(string: Cow<'a, str>) -> InlinableString {
+        match string {
+            Cow::Borrowed(s) => InlinableString::from(s),
+            Cow::Owned(s) => InlinableString::from(s),
+        }
     }
 }
 
-impl ops::Index<ops::Range<usize>> for InlinableString {
-    type Output = str;
-
+impl From<InlineString> for InlinableString {
     #[inline]
-    fn index(&self, index: ops::Range<usize>) -> &str {
-        match *self {
-            InlinableString::Heap(ref s) => s.index(index),
-            InlinableString::Inline(ref s) => s.index(index),
-        }
+    fn from(string: InlineString) -> InlinableString {
+        InlinableString::Inline(string)
     }
 }
 
-impl ops::Index<ops::RangeTo<usize>> for InlinableString {
-    type Output = str;
-
+impl<'a> From<&'a InlineString> for InlinableString {
     #[inline]
-    fn index(&self, index: ops::RangeTo<usize>) -> &str {
-        match *self {
-            InlinableString::Heap(ref s) => s.index(index),
-            InlinableString::Inline(ref s) => s.index(index),
-        }
+    fn from(string: &'a InlineString) -> InlinableString {
+        InlinableString::Inline(string.clone())
     }
 }
 
-impl ops::Index<ops::RangeFrom<usize>> for InlinableString {
-    type Output = str;
+/// Moves the buffer out of a heap-allocated string without copying; an inline
+/// string is copied into a new `String`. See `InlinableString::into_string`.
+impl From<InlinableString> for String {
+    #[inline]
+    fn from(string: InlinableString) -> String {
+        string.into_string()
+    }
+}
 
+impl<'a> From<&'a InlinableString> for Cow<'a, str> {
     #[inline]
-    fn index(&self, index: ops::RangeFrom<usize>) -> &str {
-        match *self {
-            InlinableString::Heap(ref s) => s.index(index),
-            InlinableString::Inline(ref s) => s.index(index),
-        }
+    fn from(string: &'a InlinableString) -> Cow<'a, str> {
+        Cow::Borrowed(string)
     }
 }
 
-impl ops::Index<ops::RangeFull> for InlinableString {
-    type Output = str;
+/// Converts through `String`, so a heap-allocated string's buffer is moved
+/// into the `Cow` without copying.
+impl<'a> From<InlinableString> for Cow<'a, str> {
+    #[inline]
+    fn from(string: InlinableString) -> Cow<'a, str> {
+        Cow::Owned(string.into_string())
+    }
+}
 
+/// Moves the buffer out of a heap-allocated string without copying; an inline
+/// string's bytes are copied into a new `Vec<u8>`.
+impl From<InlinableString> for Vec<u8> {
     #[inline]
-    fn index(&self, index: ops::RangeFull) -> &str {
-        match *self {
-            InlinableString::Heap(ref s) => s.index(index),
-            InlinableString::Inline(ref s) => s.index(index),
-        }
+    fn from(string: InlinableString) -> Vec<u8> {
+        string.into_bytes()
     }
 }
 
-impl ops::IndexMut<ops::Range<usize>> for InlinableString {
+/// Copies the string's bytes straight into the new shared allocation.
+impl From<InlinableString> for Arc<str> {
     #[inline]
-    fn index_mut(&mut self, index: ops::Range<usize>) -> &mut str {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.index_mut(index),
-            InlinableString::Inline(ref mut s) => s.index_mut(index),
-        }
+    fn from(string: InlinableString) -> Arc<str> {
+        Arc::from(&*string)
     }
 }
 
-impl ops::IndexMut<ops::RangeTo<usize>> for InlinableString {
+/// Copies the string's bytes straight into the new shared allocation.
+impl From<InlinableString> for Rc<str> {
     #[inline]
-    fn index_mut(&mut self, index: ops::RangeTo<usize>) -> &mut str {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.index_mut(index),
-            InlinableString::Inline(ref mut s) => s.index_mut(index),
-        }
+    fn from(string: InlinableString) -> Rc<str> {
+        Rc::from(&*string)
     }
 }
 
-impl ops::IndexMut<ops::RangeFrom<usize>> for InlinableString {
+impl From<Arc<str>> for InlinableString {
     #[inline]
-    fn index_mut(&mut self, index: ops::RangeFrom<usize>) -> &mut str {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.index_mut(index),
-            InlinableString::Inline(ref mut s) => s.index_mut(index),
-        }
+    fn from(string: Arc<str>) -> InlinableString {
+        InlinableString::from(&*string)
     }
 }
 
-impl ops::IndexMut<ops::RangeFull> for InlinableString {
+impl From<Rc<str>> for InlinableString {
     #[inline]
-    fn index_mut(&mut self, index: ops::RangeFull) -> &mut str {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.index_mut(index),
-            InlinableString::Inline(ref mut s) => s.index_mut(index),
-        }
+    fn from(string: Rc<str>) -> InlinableString {
+        InlinableString::from(&*string)
     }
 }
 
-impl ops::Deref for InlinableString {
-    type Target = str;
+/// Converts an `OsStr` that is valid Unicode, handing it back unchanged
+/// otherwise.
+///
+/// The check happens in place on every platform, via `OsStr::to_str`; only
+/// the conversion into an `InlinableString` copies.
+impl<'a> TryFrom<&'a OsStr> for InlinableString {
+    type Error = &'a OsStr;
 
     #[inline]
-    fn deref(&self) -> &str {
-        match *self {
-            InlinableString::Heap(ref s) => s.deref(),
-            InlinableString::Inline(ref s) => s.deref(),
-        }
+    fn try_from(string: &'a OsStr) -> Result<InlinableString, &'a OsStr> {
+        string.to_str().map(InlinableString::from).ok_or(string)
     }
 }
 
-impl ops::DerefMut for InlinableString {
+/// Converts an `OsString` that is valid Unicode, handing it back unchanged
+/// otherwise.
+///
+/// The check happens in place, via `OsString::into_string`, which reuses the
+/// existing buffer on every platform. Long strings therefore keep their buffer
+/// without copying, while short strings are copied inline, as with
+/// `From<String>`.
+impl TryFrom<OsString> for InlinableString {
+    type Error = OsString;
+
     #[inline]
-    fn deref_mut(&mut self) -> &mut str {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.deref_mut(),
-            InlinableString::Inline(ref mut s) => s.deref_mut(),
-        }
+    fn try_from(string: OsString) -> Result<InlinableString, OsString> {
+        string.into_string().map(InlinableString::from)
     }
 }
 
-impl PartialEq<InlinableString> for InlinableString {
+/// Converts through `String`, so a heap-allocated string's buffer is moved
+/// into the `OsString` where the platform allows it (on Unix, for instance).
+impl From<InlinableString> for OsString {
     #[inline]
-    fn eq(&self, rhs: &InlinableString) -> bool {
-        PartialEq::eq(&self[..], &rhs[..])
+    fn from(string: InlinableString) -> OsString {
+        OsString::from(string.into_string())
     }
+}
 
+/// Converts through `String`, so a heap-allocated string's buffer is moved
+/// into the `PathBuf` where the platform allows it (on Unix, for instance).
+impl From<InlinableString> for PathBuf {
     #[inline]
-    fn ne(&self, rhs: &InlinableString) -> bool {
-        PartialEq::ne(&self[..], &rhs[..])
+    fn from(string: InlinableString) -> PathBuf {
+        PathBuf::from(string.into_string())
     }
 }
 
-macro_rules! impl_eq {
-    ($lhs:ty, $rhs: ty) => {
-        impl<'a> PartialEq<$rhs> for $lhs {
-            #[inline]
-            fn eq(&self, other: &$rhs) -> bool { PartialEq::eq(&self[..], &other[..]) }
-            #[inline]
-            fn ne(&self, other: &$rhs) -> bool { PartialEq::ne(&self[..], &other[..]) }
-        }
-
-        impl<'a> PartialEq<$lhs> for $rhs {
-            #[inline]
-            fn eq(&self, other: &$lhs) -> bool { PartialEq::eq(&self[..], &other[..]) }
-            #[inline]
-            fn ne(&self, other: &$lhs) -> bool { PartialEq::ne(&self[..], &other[..]) }
-        }
+impl ToSocketAddrs for InlinableString {
+    type Iter = vec::IntoIter<SocketAddr>;
 
+    #[inline]
+    fn to_socket_addrs(&self) -> io::Result<vec::IntoIter<SocketAddr>> {
+        self[..].to_socket_addrs()
     }
 }
 
-impl_eq! { InlinableString, str }
-impl_eq! { InlinableString, String }
-impl_eq! { InlinableString, &'a str }
-impl_eq! { InlinableString, InlineString }
-impl_eq! { Cow<'a, str>, InlinableString }
+/// The error type an `InlinableString` is boxed into by
+/// `From<InlinableString> for Box<dyn Error>`, printing the string as its
+/// message.
+struct StringError(InlinableString);
+
+impl fmt::Display for StringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
 
-impl<'a> StringExt<'a> for InlinableString {
+impl fmt::Debug for StringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl error::Error for StringError {}
+
+impl From<InlinableString> for Box<dyn error::Error + Send + Sync> {
     #[inline]
-    fn new() -> Self {
-        InlinableString::Inline(InlineString::new())
+    fn from(string: InlinableString) -> Box<dyn error::Error + Send + Sync> {
+        Box::new(StringError(string))
+    }
+}
+
+impl From<InlinableString> for Box<dyn error::Error> {
+    #[inline]
+    fn from(string: InlinableString) -> Box<dyn error::Error> {
+        Box::new(StringError(string))
     }
+}
 
+impl From<Box<str>> for InlinableString {
     #[inline]
-    fn with_capacity(capacity: usize) -> Self {
-        if capacity <= INLINE_STRING_CAPACITY {
-            InlinableString::Inline(InlineString::new())
-        } else {
-            InlinableString::Heap(String::with_capacity(capacity))
+    fn from(string: Box<str>) -> InlinableString {
+        InlinableString::from(string.into_string())
+    }
+}
+
+/// Moves a heap-allocated string's buffer into the box, which only
+/// reallocates if it has spare capacity; an inline string is copied into a
+/// new, exactly sized allocation.
+impl From<InlinableString> for Box<str> {
+    #[inline]
+    fn from(string: InlinableString) -> Box<str> {
+        string.into_boxed_str()
+    }
+}
+
+impl From<char> for InlinableString {
+    #[inline]
+    fn from(ch: char) -> InlinableString {
+        let mut s = InlineString::new();
+        s.push(ch).expect("a char always fits in an InlineString");
+        InlinableString::Inline(s)
+    }
+}
+
+impl Default for InlinableString {
+    fn default() -> Self {
+        InlinableString::new()
+    }
+}
+
+impl fmt::Display for InlinableString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            InlinableString::Heap(ref s) => s.fmt(f),
+            InlinableString::Inline(ref s) => s.fmt(f),
         }
     }
+}
 
+impl InlinableString {
+    /// The capacity (in bytes) of inline storage; the same value as the
+    /// free-standing `INLINE_STRING_CAPACITY` constant.
+    pub const INLINE_CAPACITY: usize = INLINE_STRING_CAPACITY;
+
+    /// Returns true if the string is stored inline, without a heap allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("small");
+    /// assert!(s.is_inline());
+    /// s.push_str("a really long string that's bigger than `INLINE_STRING_CAPACITY`");
+    /// assert!(!s.is_inline());
+    /// ```
     #[inline]
-    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error> {
-        String::from_utf8(vec).map(InlinableString::Heap)
+    pub fn is_inline(&self) -> bool {
+        match *self {
+            InlinableString::Heap(_) => false,
+            InlinableString::Inline(_) => true,
+        }
     }
 
+    /// Returns true if the string is stored in a heap-allocated `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::with_capacity(100);
+    /// assert!(s.is_heap());
+    /// s.shrink_to_fit();
+    /// assert!(!s.is_heap());
+    /// ```
     #[inline]
-    fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> {
-        String::from_utf16(v).map(InlinableString::Heap)
+    pub fn is_heap(&self) -> bool {
+        !self.is_inline()
     }
 
+    /// Returns the number of bytes this string has allocated on the heap: zero
+    /// for inline strings, and the `String`'s capacity otherwise.
+    ///
+    /// Unlike `capacity`, this does not count inline storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("small");
+    /// assert_eq!(s.heap_allocated_bytes(), 0);
+    ///
+    /// let s = InlinableString::with_capacity(100);
+    /// assert_eq!(s.heap_allocated_bytes(), s.capacity());
+    /// ```
     #[inline]
-    fn from_utf16_lossy(v: &[u16]) -> Self {
-        InlinableString::Heap(String::from_utf16_lossy(v))
+    pub fn heap_allocated_bytes(&self) -> usize {
+        match *self {
+            InlinableString::Heap(ref s) => s.capacity(),
+            InlinableString::Inline(_) => 0,
+        }
     }
 
+    /// Returns the total number of bytes this string occupies: the size of the
+    /// `InlinableString` itself plus any heap allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem;
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::with_capacity(100);
+    /// assert_eq!(s.total_size_bytes(),
+    ///            mem::size_of::<InlinableString>() + s.capacity());
+    /// ```
     #[inline]
-    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self {
-        InlinableString::Heap(String::from_raw_parts(buf, length, capacity))
+    pub fn total_size_bytes(&self) -> usize {
+        mem::size_of::<InlinableString>() + self.heap_allocated_bytes()
     }
 
+    /// Returns the inline string buffer if the string is stored inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("small");
+    /// assert_eq!(s.as_inline().unwrap(), "small");
+    /// ```
     #[inline]
-    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self {
-        InlinableString::Heap(String::from_utf8_unchecked(bytes))
+    pub fn as_inline(&self) -> Option<&InlineString> {
+        match *self {
+            InlinableString::Heap(_) => None,
+            InlinableString::Inline(ref s) => Some(s),
+        }
     }
 
+    /// Returns the heap-allocated `String` if the string is stored on the
+    /// heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::with_capacity(100);
+    /// assert!(s.as_heap_string().unwrap().capacity() >= 100);
+    /// ```
     #[inline]
-    fn into_bytes(self) -> Vec<u8> {
-        match self {
-            InlinableString::Heap(s) => s.into_bytes(),
-            InlinableString::Inline(s) => Vec::from(&s[..]),
+    pub fn as_heap_string(&self) -> Option<&String> {
+        match *self {
+            InlinableString::Heap(ref s) => Some(s),
+            InlinableString::Inline(_) => None,
         }
     }
 
+    /// Returns the heap-allocated `String` mutably if the string is stored on
+    /// the heap, giving access to `String`-only APIs such as `as_mut_vec`.
+    ///
+    /// The `String` stays in the heap variant no matter what is done with it:
+    /// shrinking it below `INLINE_STRING_CAPACITY` bytes does not demote it to
+    /// inline storage. Call `shrink_to_fit` afterwards for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::with_capacity(100);
+    /// s.as_mut_heap_string().unwrap().push_str("hello");
+    /// assert_eq!(s, "hello");
+    /// assert!(s.is_heap());
+    /// ```
     #[inline]
-    fn push_str(&mut self, string: &str) {
-        let promoted = match *self {
-            InlinableString::Inline(ref mut s) => {
-                if s.push_str(string).is_ok() {
-                    return;
-                }
-                let mut promoted = String::with_capacity(string.len() + s.len());
-                promoted.push_str(&*s);
-                promoted.push_str(string);
-                promoted
-            },
-            InlinableString::Heap(ref mut s) => {
-                s.push_str(string);
-                return;
-            },
-        };
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+    pub fn as_mut_heap_string(&mut self) -> Option<&mut String> {
+        match *self {
+            InlinableString::Heap(ref mut s) => Some(s),
+            InlinableString::Inline(_) => None,
+        }
     }
 
+    /// Returns a mutable string slice of the whole string, whichever variant
+    /// it is stored in.
+    ///
+    /// This is the same as `StringExt::as_mut_str` and `DerefMut`, but doesn't
+    /// need `StringExt` in scope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("foobar");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(s, "FOOBAR");
+    /// ```
     #[inline]
-    fn capacity(&self) -> usize {
+    pub fn as_mut_str(&mut self) -> &mut str {
         match *self {
-            InlinableString::Heap(ref s) => s.capacity(),
-            InlinableString::Inline(_) => INLINE_STRING_CAPACITY,
+            InlinableString::Heap(ref mut s) => s.as_mut_str(),
+            InlinableString::Inline(ref mut s) => &mut s[..],
         }
     }
 
+    /// Empties the string and returns it to inline storage, freeing its heap
+    /// allocation if it has one.
+    ///
+    /// `clear` keeps a heap string's capacity so that it can be reused as a
+    /// buffer without reallocating; use this when the allocation should be
+    /// dropped instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::with_capacity(100);
+    /// s.push_str("hello");
+    ///
+    /// s.clear();
+    /// assert!(s.is_heap());
+    /// assert!(s.capacity() >= 100);
+    ///
+    /// s.reset_to_inline();
+    /// assert!(s.is_empty());
+    /// assert!(s.is_inline());
+    /// ```
     #[inline]
-    fn reserve(&mut self, additional: usize) {
-        let promoted = match *self {
-            InlinableString::Inline(ref s) => {
-                let new_capacity = s.len() + additional;
-                if new_capacity <= INLINE_STRING_CAPACITY {
-                    return;
-                }
-                let mut promoted = String::with_capacity(new_capacity);
-                promoted.push_str(&s);
-                promoted
-            },
-            InlinableString::Heap(ref mut s) => {
-                s.reserve(additional);
-                return;
-            },
-        };
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+    pub fn reset_to_inline(&mut self) {
+        *self = InlinableString::Inline(InlineString::new());
     }
 
+    /// Replaces all matches of `from` with `to`, returning the result as a new
+    /// `InlinableString`.
+    ///
+    /// This behaves like `str::replace`, but the result stays inline when it
+    /// fits rather than always allocating a `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("a-b-c");
+    /// let replaced = s.replace("-", "+");
+    /// assert_eq!(replaced, "a+b+c");
+    /// assert!(replaced.is_inline());
+    /// ```
     #[inline]
-    fn reserve_exact(&mut self, additional: usize) {
-        let promoted = match *self {
-            InlinableString::Inline(ref s) => {
-                let new_capacity = s.len() + additional;
-                if new_capacity <= INLINE_STRING_CAPACITY {
-                    return;
-                }
-                let mut promoted = String::with_capacity(new_capacity);
-                promoted.push_str(&s);
-                promoted
-            },
-            InlinableString::Heap(ref mut s) => {
-                s.reserve_exact(additional);
-                return;
-            },
-        };
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+    pub fn replace(&self, from: &str, to: &str) -> InlinableString {
+        self.replacen(from, to, usize::MAX)
     }
 
+    /// Replaces the first `count` matches of `from` with `to`, returning the
+    /// result as a new `InlinableString`.
+    ///
+    /// This behaves like `str::replacen`, but the result stays inline when it
+    /// fits rather than always allocating a `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("a-b-c");
+    /// assert_eq!(s.replacen("-", "+", 1), "a+b-c");
+    /// ```
+    pub fn replacen(&self, from: &str, to: &str, count: usize) -> InlinableString {
+        let mut result = InlinableString::new();
+        let mut last_end = 0;
+        for (start, part) in self.match_indices(from).take(count) {
+            result.push_str(&self[last_end..start]);
+            result.push_str(to);
+            last_end = start + part.len();
+        }
+        result.push_str(&self[last_end..]);
+        result
+    }
+
+    /// Returns the lowercase equivalent of this string as a new
+    /// `InlinableString`.
+    ///
+    /// This behaves like `str::to_lowercase`, but the result stays inline when
+    /// it fits. Lowercasing can change the length in bytes, so that is decided
+    /// by the length of the result rather than of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("HELLO");
+    /// let lower = s.to_lowercase();
+    /// assert_eq!(lower, "hello");
+    /// assert!(lower.is_inline());
+    /// ```
     #[inline]
-    fn shrink_to_fit(&mut self) {
-        if self.len() <= INLINE_STRING_CAPACITY {
-            let demoted = if let InlinableString::Heap(ref s) = *self {
-                InlineString::from(&s[..])
-            } else {
-                return;
-            };
-            mem::swap(self, &mut InlinableString::Inline(demoted));
+    pub fn to_lowercase(&self) -> InlinableString {
+        InlinableString::from_str_to_lowercase(self)
+    }
+
+    /// Returns the uppercase equivalent of this string as a new
+    /// `InlinableString`.
+    ///
+    /// This behaves like `str::to_uppercase`, but the result stays inline when
+    /// it fits. Uppercasing can change the length in bytes, so that is decided
+    /// by the length of the result rather than of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("straße");
+    /// assert_eq!(s.to_uppercase(), "STRASSE");
+    /// ```
+    #[inline]
+    pub fn to_uppercase(&self) -> InlinableString {
+        InlinableString::from_str_to_uppercase(self)
+    }
+
+    /// Returns a copy of this string with ASCII letters lowercased, as
+    /// `str::to_ascii_lowercase` does. Non-ASCII characters are unchanged.
+    ///
+    /// The length doesn't change, so the copy is inline if `self` fits
+    /// inline, and otherwise allocated once with exactly the needed capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("Grüße, JÜRGEN");
+    /// assert_eq!(s.to_ascii_lowercase(), "grüße, jÜrgen");
+    /// ```
+    #[inline]
+    pub fn to_ascii_lowercase(&self) -> InlinableString {
+        InlinableString::from_str_to_ascii_lowercase(self)
+    }
+
+    /// Returns a copy of this string with ASCII letters uppercased, as
+    /// `str::to_ascii_uppercase` does. Non-ASCII characters are unchanged.
+    ///
+    /// The length doesn't change, so the copy is inline if `self` fits
+    /// inline, and otherwise allocated once with exactly the needed capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("Grüße, Jürgen");
+    /// assert_eq!(s.to_ascii_uppercase(), "GRüßE, JüRGEN");
+    /// ```
+    #[inline]
+    pub fn to_ascii_uppercase(&self) -> InlinableString {
+        InlinableString::from_str_to_ascii_uppercase(self)
+    }
+
+    /// Converts ASCII letters in this string to lowercase in place, as
+    /// `str::make_ascii_lowercase` does. Non-ASCII characters are unchanged.
+    ///
+    /// This never changes the length, so it never allocates or moves the
+    /// string between inline and heap storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("Grüße, JÜRGEN");
+    /// s.make_ascii_lowercase();
+    /// assert_eq!(s, "grüße, jÜrgen");
+    /// ```
+    #[inline]
+    pub fn make_ascii_lowercase(&mut self) {
+        self.as_mut_str().make_ascii_lowercase()
+    }
+
+    /// Converts ASCII letters in this string to uppercase in place, as
+    /// `str::make_ascii_uppercase` does. Non-ASCII characters are unchanged.
+    ///
+    /// This never changes the length, so it never allocates or moves the
+    /// string between inline and heap storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("Grüße, Jürgen");
+    /// s.make_ascii_uppercase();
+    /// assert_eq!(s, "GRüßE, JüRGEN");
+    /// ```
+    #[inline]
+    pub fn make_ascii_uppercase(&mut self) {
+        self.as_mut_str().make_ascii_uppercase()
+    }
+
+    /// Converts this string to lowercase in place, as `str::to_lowercase`
+    /// does.
+    ///
+    /// If every char lowercases to the same number of bytes, which is the
+    /// common case, the string is rewritten within its existing buffer.
+    /// Otherwise it is rebuilt, inline if the result fits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("ÉCOLE");
+    /// s.make_lowercase();
+    /// assert_eq!(s, "école");
+    /// ```
+    pub fn make_lowercase(&mut self) {
+        if self.is_ascii() {
+            self.make_ascii_lowercase();
             return;
         }
+        // A capital sigma's lowercase depends on the chars around it, so
+        // leave it to `to_lowercase`.
+        if self.contains('Σ') || !case_map_in_place(self.as_mut_str(), char::to_lowercase) {
+            *self = self.to_lowercase();
+        }
+    }
 
-        match *self {
-            InlinableString::Heap(ref mut s) => s.shrink_to_fit(),
-            _ => panic!("inlinable_string: internal error: this branch should be unreachable"),
+    /// Converts this string to uppercase in place, as `str::to_uppercase`
+    /// does.
+    ///
+    /// If every char uppercases to the same number of bytes, which is the
+    /// common case, the string is rewritten within its existing buffer.
+    /// Otherwise it is rebuilt, inline if the result fits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("straße");
+    /// s.make_uppercase();
+    /// assert_eq!(s, "STRASSE");
+    /// ```
+    pub fn make_uppercase(&mut self) {
+        if self.is_ascii() {
+            self.make_ascii_uppercase();
+            return;
+        }
+        if !case_map_in_place(self.as_mut_str(), char::to_uppercase) {
+            *self = self.to_uppercase();
+        }
+    }
+
+    /// Returns a copy of this string with its first char uppercased and the
+    /// rest unchanged.
+    ///
+    /// The first char may uppercase to several chars, as `'ß'` does to
+    /// `"SS"`. The length of the result is computed first, so it is stored
+    /// inline if it fits and otherwise allocated once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("monday");
+    /// let capitalized = s.capitalize();
+    /// assert_eq!(capitalized, "Monday");
+    /// assert!(capitalized.is_inline());
+    /// ```
+    pub fn capitalize(&self) -> InlinableString {
+        let mut chars = self.chars();
+        let first = match chars.next() {
+            Some(first) => first,
+            None => return InlinableString::new(),
+        };
+        let rest = chars.as_str();
+
+        let len = first.to_uppercase().map(char::len_utf8).sum::<usize>() + rest.len();
+        let mut s = if len <= INLINE_STRING_CAPACITY {
+            InlinableString::new()
+        } else {
+            InlinableString::Heap(String::with_capacity(len))
         };
+        s.extend(first.to_uppercase());
+        s.push_str(rest);
+        s
     }
 
-    #[inline]
-    fn push(&mut self, ch: char) {
-        let promoted = match *self {
-            InlinableString::Inline(ref mut s) => {
-                if s.push(ch).is_ok() {
-                    return;
+    /// Uppercases the first char of this string in place, leaving the rest
+    /// unchanged.
+    ///
+    /// If the uppercase of the first char is the same number of bytes, it is
+    /// overwritten within the existing buffer. Otherwise the string is
+    /// rebuilt as by `capitalize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("école");
+    /// s.capitalize_in_place();
+    /// assert_eq!(s, "École");
+    /// ```
+    pub fn capitalize_in_place(&mut self) {
+        let first_len = match self.chars().next() {
+            Some(first) => first.len_utf8(),
+            None => return,
+        };
+        if !case_map_in_place(&mut self.as_mut_str()[..first_len], char::to_uppercase) {
+            *self = self.capitalize();
+        }
+    }
+
+    /// Reverses the order of the chars in this string, within its existing
+    /// buffer.
+    ///
+    /// This reverses `char`s, not grapheme clusters, so a combining mark ends
+    /// up before the char it originally followed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("añb");
+    /// s.reverse_in_place();
+    /// assert_eq!(s, "bña");
+    /// ```
+    pub fn reverse_in_place(&mut self) {
+        unsafe {
+            let bytes = self.as_mut_slice();
+            bytes.reverse();
+            // Reversing the bytes also reversed each multi-byte char, leaving
+            // its continuation bytes before its leading byte; put them back.
+            let mut start = 0;
+            while start < bytes.len() {
+                let mut end = start;
+                while bytes[end] & 0xC0 == 0x80 {
+                    end += 1;
                 }
+                bytes[start..end + 1].reverse();
+                start = end + 1;
+            }
+        }
+    }
 
-                let mut promoted = String::with_capacity(s.len() + 1);
-                promoted.push_str(&*s);
-                promoted.push(ch);
-                promoted
-            },
-            InlinableString::Heap(ref mut s) => {
-                s.push(ch);
-                return;
-            },
-        };
+    /// Removes all leading and trailing occurrences of `ch` in place, without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("\"quoted\"");
+    /// s.trim_matches_in_place('"');
+    /// assert_eq!(s, "quoted");
+    /// ```
+    #[inline]
+    pub fn trim_matches_in_place(&mut self, ch: char) {
+        self.trim_matches_in_place_with(|c| c == ch)
+    }
 
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+    /// Removes all leading occurrences of `ch` in place, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("//path/");
+    /// s.trim_start_matches_in_place('/');
+    /// assert_eq!(s, "path/");
+    /// ```
+    #[inline]
+    pub fn trim_start_matches_in_place(&mut self, ch: char) {
+        self.trim_start_matches_in_place_with(|c| c == ch)
     }
 
+    /// Removes all trailing occurrences of `ch` in place, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("//path/");
+    /// s.trim_end_matches_in_place('/');
+    /// assert_eq!(s, "//path");
+    /// ```
     #[inline]
-    fn as_bytes(&self) -> &[u8] {
-        match *self {
-            InlinableString::Heap(ref s) => s.as_bytes(),
-            InlinableString::Inline(ref s) => s.as_bytes(),
+    pub fn trim_end_matches_in_place(&mut self, ch: char) {
+        self.trim_end_matches_in_place_with(|c| c == ch)
+    }
+
+    /// Removes all leading and trailing chars that satisfy `f` in place,
+    /// without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("123abc456");
+    /// s.trim_matches_in_place_with(|c| c.is_ascii_digit());
+    /// assert_eq!(s, "abc");
+    /// ```
+    pub fn trim_matches_in_place_with<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        // Trim the end first, so there is less left to move when trimming
+        // the start.
+        self.trim_end_matches_in_place_with(&mut f);
+        self.trim_start_matches_in_place_with(f);
+    }
+
+    /// Removes all leading chars that satisfy `f` in place, without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("123abc456");
+    /// s.trim_start_matches_in_place_with(|c| c.is_ascii_digit());
+    /// assert_eq!(s, "abc456");
+    /// ```
+    pub fn trim_start_matches_in_place_with<F: FnMut(char) -> bool>(&mut self, f: F) {
+        let start = self.len() - self.trim_start_matches(f).len();
+        self.replace_range(..start, "");
+    }
+
+    /// Removes all trailing chars that satisfy `f` in place, without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("123abc456");
+    /// s.trim_end_matches_in_place_with(|c| c.is_ascii_digit());
+    /// assert_eq!(s, "123abc");
+    /// ```
+    pub fn trim_end_matches_in_place_with<F: FnMut(char) -> bool>(&mut self, f: F) {
+        let len = self.trim_end_matches(f).len();
+        self.truncate(len);
+    }
+
+    /// Removes `prefix` from the start of this string in place, if it is
+    /// there, without allocating.
+    ///
+    /// Returns true if the string started with `prefix`, which is exactly
+    /// when `str::strip_prefix` would return `Some`. An empty prefix always
+    /// matches and removes nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("v1.2.3");
+    /// assert!(s.strip_prefix_in_place("v"));
+    /// assert_eq!(s, "1.2.3");
+    /// assert!(!s.strip_prefix_in_place("v"));
+    /// ```
+    pub fn strip_prefix_in_place(&mut self, prefix: &str) -> bool {
+        if !self.starts_with(prefix) {
+            return false;
+        }
+        self.replace_range(..prefix.len(), "");
+        true
+    }
+
+    /// Removes `suffix` from the end of this string in place, if it is
+    /// there, without allocating.
+    ///
+    /// Returns true if the string ended with `suffix`, which is exactly when
+    /// `str::strip_suffix` would return `Some`. An empty suffix always
+    /// matches and removes nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("report.txt");
+    /// assert!(s.strip_suffix_in_place(".txt"));
+    /// assert_eq!(s, "report");
+    /// assert!(!s.strip_suffix_in_place(".txt"));
+    /// ```
+    pub fn strip_suffix_in_place(&mut self, suffix: &str) -> bool {
+        if !self.ends_with(suffix) {
+            return false;
+        }
+        let len = self.len() - suffix.len();
+        self.truncate(len);
+        true
+    }
+
+    /// Replaces all matches of `from` with `to` in place.
+    ///
+    /// When `to` is no longer than `from`, the string is compacted within its
+    /// existing buffer, inline or heap, and never allocates. Otherwise the
+    /// result may not fit, so this falls back to `replace` and assigns the
+    /// new string to `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("one\r\ntwo\r\n");
+    /// s.replace_in_place("\r\n", "\n");
+    /// assert_eq!(s, "one\ntwo\n");
+    /// ```
+    pub fn replace_in_place(&mut self, from: &str, to: &str) {
+        if to.len() > from.len() {
+            *self = self.replace(from, to);
+            return;
+        }
+        if from.is_empty() {
+            return;
+        }
+        let new_len = unsafe { replace_shrinking(self.as_mut_slice(), from, to) };
+        self.truncate(new_len);
+    }
+
+    /// Replaces the contents of this string with `s`, reusing the existing
+    /// storage where possible.
+    ///
+    /// A heap buffer that is already large enough is kept, however short `s`
+    /// is, so a scratch string can be refilled without reallocating.
+    /// Otherwise this is the same as `*self = InlinableString::from(s)`:
+    /// `s` is stored inline if it fits, and allocated exactly if not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut scratch = InlinableString::with_capacity(100);
+    /// scratch.assign("short");
+    /// assert_eq!(scratch, "short");
+    /// assert!(scratch.capacity() >= 100);
+    /// ```
+    pub fn assign(&mut self, s: &str) {
+        if let InlinableString::Heap(ref mut buf) = *self {
+            if buf.capacity() >= s.len() {
+                buf.clear();
+                buf.push_str(s);
+                return;
+            }
+        }
+        *self = InlinableString::from(s);
+    }
+
+    /// Resizes the string to `new_len` bytes, either truncating it or
+    /// appending copies of `ch`. An inline string is only promoted to the heap
+    /// if `new_len` is greater than `INLINE_STRING_CAPACITY`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if shrinking and `new_len` is not a character boundary, or if
+    /// growing and the number of bytes to add is not a multiple of
+    /// `ch.len_utf8()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("id");
+    /// s.resize(8, ' ');
+    /// assert_eq!(s, "id      ");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn resize(&mut self, new_len: usize, ch: char) {
+        if new_len <= self.len() {
+            self.truncate(new_len);
+            return;
+        }
+
+        let additional = new_len - self.len();
+        let count = additional / ch.len_utf8();
+        assert!(count * ch.len_utf8() == additional,
+                "inlinable_string::InlinableString::resize: new_len cannot be reached by
+                 appending whole characters");
+        self.reserve(additional);
+
+        let mut buf = [0; 4];
+        let encoded = ch.encode_utf8(&mut buf);
+        for _ in 0..count {
+            self.push_str(encoded);
+        }
+    }
+
+    /// Appends every string in `parts` to the end of this string.
+    ///
+    /// Unlike `extend`, which has to take the strings one at a time, this sums
+    /// their lengths first. The string stays inline if the result fits, and
+    /// otherwise grows at most once, so each part is copied exactly once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let parts = vec![InlinableString::from("foo"), InlinableString::from("bar")];
+    /// let mut s = InlinableString::from("> ");
+    /// s.extend_from_slice(&parts);
+    /// assert_eq!(s, "> foobar");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn extend_from_slice<S: AsRef<str>>(&mut self, parts: &[S]) {
+        let additional = parts.iter()
+            .try_fold(0usize, |len, part| len.checked_add(part.as_ref().len()))
+            .expect("capacity overflow");
+        // Does nothing while the result still fits inline.
+        self.reserve(additional);
+        for part in parts {
+            self.push_str(part.as_ref());
         }
     }
 
+    /// Creates a new, empty `InlinableString`, treating `capacity` only as a
+    /// hint that doesn't allocate.
+    ///
+    /// `StringExt::with_capacity` allocates a heap string up front whenever
+    /// `capacity` exceeds `INLINE_STRING_CAPACITY`. This always starts inline
+    /// instead, and the hint isn't remembered: `capacity()` reports
+    /// `INLINE_STRING_CAPACITY` until an append overflows the inline buffer,
+    /// and that append sizes the new heap buffer from the contents, as any
+    /// promotion does. See `reserve_lazy`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt, INLINE_STRING_CAPACITY};
+    ///
+    /// let mut s = InlinableString::with_capacity_lazy(100);
+    /// assert!(s.is_inline());
+    /// assert_eq!(s.capacity(), INLINE_STRING_CAPACITY);
+    ///
+    /// s.push_str("small");
+    /// assert!(s.is_inline());
+    /// ```
     #[inline]
-    fn truncate(&mut self, new_len: usize) {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.truncate(new_len),
-            InlinableString::Inline(ref mut s) => s.truncate(new_len),
-        };
+    pub fn with_capacity_lazy(capacity: usize) -> InlinableString {
+        // Nothing to do with the hint: an inline string has no room to store
+        // it, and allocating now is what this avoids.
+        let _ = capacity;
+        InlinableString::new()
     }
 
+    /// Hints that at least `additional` more bytes are likely to be appended,
+    /// without allocating while the string is still inline.
+    ///
+    /// `StringExt::reserve` guarantees `capacity() >= len() + additional`
+    /// afterwards, so it promotes an inline string to the heap as soon as the
+    /// request exceeds `INLINE_STRING_CAPACITY`. This method only treats the
+    /// request as advisory: an inline string stays inline, and `capacity()`
+    /// keeps reporting `INLINE_STRING_CAPACITY`, until an append actually
+    /// overflows it. The request isn't remembered, so that append sizes the
+    /// heap buffer from the contents rather than from `additional`. A heap
+    /// string reserves as `String::reserve` does.
+    ///
+    /// Use this for speculative reservations where the string usually ends up
+    /// small. Use `reserve` or `reserve_exact` when the capacity must be there
+    /// up front; they allocate eagerly whether or not `reserve_lazy` was
+    /// called first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt, INLINE_STRING_CAPACITY};
+    ///
+    /// let mut s = InlinableString::new();
+    /// s.reserve_lazy(100);
+    /// assert!(s.is_inline());
+    /// assert_eq!(s.capacity(), INLINE_STRING_CAPACITY);
+    ///
+    /// s.push_str("small");
+    /// assert!(s.is_inline());
+    /// ```
     #[inline]
-    fn pop(&mut self) -> Option<char> {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.pop(),
-            InlinableString::Inline(ref mut s) => s.pop(),
+    pub fn reserve_lazy(&mut self, additional: usize) {
+        if let InlinableString::Heap(ref mut s) = *self {
+            s.reserve(additional);
         }
     }
 
+    /// Converts a slice of bytes to an `InlinableString`, replacing any
+    /// invalid UTF-8 sequences with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// Unlike `StringExt::from_utf8_lossy`, which returns a `Cow<str>` built
+    /// by `String::from_utf8_lossy`, this builds the result directly, so it is
+    /// stored inline whenever it fits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_utf8_lossy_inlined(b"Hello \xF0\x90\x80World");
+    /// assert_eq!(s, "Hello \u{FFFD}World");
+    /// assert!(s.is_inline());
+    /// ```
     #[inline]
-    fn remove(&mut self, idx: usize) -> char {
-        match *self {
-            InlinableString::Heap(ref mut s) => s.remove(idx),
-            InlinableString::Inline(ref mut s) => s.remove(idx),
+    pub fn from_utf8_lossy_inlined(v: &[u8]) -> InlinableString {
+        if let Ok(valid) = validate_utf8(v) {
+            return InlinableString::from(valid);
         }
+        let mut s = InlinableString::new();
+        s.push_utf8_lossy(v);
+        s
     }
 
+    /// Converts an `OsStr` to an `InlinableString`, replacing any sequences
+    /// that aren't valid Unicode with U+FFFD REPLACEMENT CHARACTER, like
+    /// `OsStr::to_string_lossy`.
+    ///
+    /// The result is stored inline whenever it fits. On Unix the bytes are
+    /// decoded straight into the result; elsewhere the string is converted
+    /// with `to_string_lossy` and any owned result is moved in without
+    /// copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ffi::OsStr;
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_os_str_lossy(OsStr::new("file.txt"));
+    /// assert_eq!(s, "file.txt");
+    /// assert!(s.is_inline());
+    /// ```
     #[inline]
-    fn insert(&mut self, idx: usize, ch: char) {
-        let promoted = match *self {
-            InlinableString::Heap(ref mut s) => {
-                s.insert(idx, ch);
-                return;
-            },
-            InlinableString::Inline(ref mut s) => {
-                if s.insert(idx, ch).is_ok() {
-                    return;
-                }
+    pub fn from_os_str_lossy(s: &OsStr) -> InlinableString {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            InlinableString::from_utf8_lossy_inlined(s.as_bytes())
+        }
+        #[cfg(not(unix))]
+        {
+            InlinableString::from(s.to_string_lossy())
+        }
+    }
 
-                let mut promoted = String::with_capacity(s.len() + 1);
-                promoted.push_str(&s[..idx]);
-                promoted.push(ch);
-                promoted.push_str(&s[idx..]);
-                promoted
-            },
-        };
+    /// Converts a slice of bytes to an `InlinableString`, copying it into
+    /// inline storage when it fits and into a new `String` otherwise.
+    ///
+    /// Unlike `StringExt::from_utf8`, this doesn't require an owned `Vec<u8>`,
+    /// so there is no buffer to hand back on error and a `str::Utf8Error` is
+    /// returned instead of a `FromUtf8Error`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_utf8_slice(b"hello").unwrap();
+    /// assert_eq!(s, "hello");
+    /// assert!(s.is_inline());
+    ///
+    /// assert!(InlinableString::from_utf8_slice(b"\xFF").is_err());
+    /// ```
+    #[inline]
+    pub fn from_utf8_slice(v: &[u8]) -> Result<InlinableString, str::Utf8Error> {
+        validate_utf8(v).map(InlinableString::from)
+    }
+
+    /// Converts a slice of ASCII bytes to an `InlinableString`, copying it
+    /// into inline storage when it fits and into a new `String` otherwise.
+    ///
+    /// Checking that every byte is ASCII is cheaper than full UTF-8
+    /// validation, which makes this a faster alternative to `from_utf8_slice`
+    /// for inputs that are known to be ASCII.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `NotAsciiError` reporting the index of the first non-ASCII
+    /// byte if `bytes` is not entirely ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_ascii(b"hello").unwrap();
+    /// assert_eq!(s, "hello");
+    /// assert!(s.is_inline());
+    ///
+    /// let err = InlinableString::from_ascii(b"caf\xC3\xA9").unwrap_err();
+    /// assert_eq!(err.index(), 3);
+    /// ```
+    #[inline]
+    pub fn from_ascii(bytes: &[u8]) -> Result<InlinableString, NotAsciiError> {
+        if bytes.is_ascii() {
+            Ok(unsafe { InlinableString::from_ascii_unchecked(bytes) })
+        } else {
+            let index = bytes.iter().position(|b| !b.is_ascii()).unwrap();
+            Err(NotAsciiError { index })
+        }
+    }
+
+    /// Converts a slice of bytes to an `InlinableString` without checking
+    /// that it is ASCII.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8; this is always the case when it is
+    /// entirely ASCII. See `from_ascii` for the checked version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = unsafe { InlinableString::from_ascii_unchecked(b"hello") };
+    /// assert_eq!(s, "hello");
+    /// ```
+    #[inline]
+    pub unsafe fn from_ascii_unchecked(bytes: &[u8]) -> InlinableString {
+        InlinableString::from(str::from_utf8_unchecked(bytes))
+    }
+
+    /// Converts this string into its bytes without allocating. An inline
+    /// string's bytes stay inline; a heap-allocated string hands over its
+    /// buffer without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let bytes = InlinableString::from("small").into_bytes_inlined();
+    /// assert!(bytes.is_inline());
+    /// assert_eq!(&bytes[..], b"small");
+    /// ```
+    #[inline]
+    pub fn into_bytes_inlined(self) -> InlinableBytes {
+        match self {
+            InlinableString::Heap(s) => InlinableBytes::from(s.into_bytes()),
+            InlinableString::Inline(ref s) => InlinableBytes::from(s),
+        }
+    }
+
+    /// Wraps `buf` as a heap-allocated `InlinableString`, keeping its buffer
+    /// and capacity however short its contents are.
+    ///
+    /// `From<String>` copies short strings inline and drops their allocation,
+    /// which is what you want for a value that stays small. When the string
+    /// is going to keep growing, that just means promoting it again later;
+    /// this constructor keeps the existing buffer to append into instead, at
+    /// the cost of holding on to the allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut buf = String::with_capacity(100);
+    /// buf.push_str("small");
+    ///
+    /// let s = InlinableString::from_string_buffer(buf);
+    /// assert!(s.is_heap());
+    /// assert!(s.capacity() >= 100);
+    /// ```
+    #[inline]
+    pub fn from_string_buffer(buf: String) -> InlinableString {
+        InlinableString::Heap(buf)
+    }
+
+    /// Creates a string made of `ch` repeated `n` times.
+    ///
+    /// The length is computed up front, so the result is either stored inline
+    /// or allocated on the heap exactly once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length in bytes overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let indent = InlinableString::from_char_repeated(' ', 4);
+    /// assert_eq!(indent, "    ");
+    /// assert!(indent.is_inline());
+    /// ```
+    pub fn from_char_repeated(ch: char, n: usize) -> InlinableString {
+        let mut buf = [0; 4];
+        let encoded = ch.encode_utf8(&mut buf);
+        let len = encoded.len().checked_mul(n).expect("capacity overflow");
+        if len <= INLINE_STRING_CAPACITY {
+            let mut s = InlineString::new();
+            for _ in 0..n {
+                s.push_str(encoded).expect("checked against INLINE_STRING_CAPACITY above");
+            }
+            InlinableString::Inline(s)
+        } else {
+            InlinableString::Heap(encoded.repeat(n))
+        }
+    }
+
+    /// Creates the lowercase equivalent of `s`, as `str::to_lowercase` does.
+    ///
+    /// The chars are case-mapped straight into the new string, so it is
+    /// stored inline if the lowercased string fits, whatever the length of
+    /// `s`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_str_to_lowercase("HELLO");
+    /// assert_eq!(s, "hello");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn from_str_to_lowercase(s: &str) -> InlinableString {
+        // A capital sigma lowercases differently at the end of a word, which
+        // only `str::to_lowercase` knows how to handle.
+        if s.contains('Σ') {
+            InlinableString::from(s.to_lowercase())
+        } else {
+            s.chars().flat_map(char::to_lowercase).collect()
+        }
+    }
+
+    /// Creates the uppercase equivalent of `s`, as `str::to_uppercase` does.
+    ///
+    /// The chars are case-mapped straight into the new string, so it is
+    /// stored inline if the uppercased string fits, whatever the length of
+    /// `s`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_str_to_uppercase("straße");
+    /// assert_eq!(s, "STRASSE");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn from_str_to_uppercase(s: &str) -> InlinableString {
+        s.chars().flat_map(char::to_uppercase).collect()
+    }
+
+    /// Creates a copy of `s` with ASCII letters lowercased, as
+    /// `str::to_ascii_lowercase` does. Non-ASCII characters are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_str_to_ascii_lowercase("GRÜßE");
+    /// assert_eq!(s, "grÜße");
+    /// ```
+    pub fn from_str_to_ascii_lowercase(s: &str) -> InlinableString {
+        let mut s = InlinableString::from(s);
+        s.make_ascii_lowercase();
+        s
+    }
+
+    /// Creates a copy of `s` with ASCII letters uppercased, as
+    /// `str::to_ascii_uppercase` does. Non-ASCII characters are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_str_to_ascii_uppercase("grüße");
+    /// assert_eq!(s, "GRüßE");
+    /// ```
+    pub fn from_str_to_ascii_uppercase(s: &str) -> InlinableString {
+        let mut s = InlinableString::from(s);
+        s.make_ascii_uppercase();
+        s
+    }
+
+    /// Converts this string into a `String`. A heap-allocated string is moved
+    /// out without copying; an inline string is copied into a new `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("hello");
+    /// let t: String = s.into_string();
+    /// assert_eq!(t, "hello");
+    /// ```
+    #[inline]
+    pub fn into_string(self) -> String {
+        match self {
+            InlinableString::Heap(s) => s,
+            InlinableString::Inline(s) => String::from(&s[..]),
+        }
+    }
+
+    /// Converts this string into an `InlineString` if its contents fit in
+    /// `INLINE_STRING_CAPACITY` bytes, copying them out of a heap-allocated
+    /// string if need be. Otherwise the original string is returned unchanged
+    /// as the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("small");
+    /// assert_eq!(s.try_into_inline().unwrap(), "small");
+    ///
+    /// let long = "a string that is too long to be stored inline";
+    /// let s = InlinableString::from(long);
+    /// assert_eq!(s.try_into_inline().unwrap_err(), long);
+    /// ```
+    #[inline]
+    pub fn try_into_inline(self) -> Result<InlineString, InlinableString> {
+        match self {
+            InlinableString::Inline(s) => Ok(s),
+            InlinableString::Heap(ref s) if s.len() <= INLINE_STRING_CAPACITY => {
+                Ok(InlineString::from(&s[..]))
+            },
+            heap => Err(heap),
+        }
+    }
+
+    /// Writes formatted output onto the end of this string.
+    ///
+    /// Both `fmt::Write` and `StringExtWrite` provide a `write_fmt` method;
+    /// this inherent one takes precedence over both, so `write!` works no
+    /// matter which of the traits are in scope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    /// use inlinable_string::{InlinableString, StringExt, StringExtWrite};
+    ///
+    /// let mut s = InlinableString::new();
+    /// write!(s, "{}-{}", 1, 2).unwrap();
+    /// assert_eq!(s, "1-2");
+    /// ```
+    #[inline]
+    pub fn write_fmt(&mut self, args: fmt::Arguments) -> fmt::Result {
+        StringExtWrite::write_fmt(self, args)
+    }
+
+    /// Appends the given `char` to the end of this string without ever
+    /// allocating.
+    ///
+    /// Unlike `StringExt::try_push`, which only fails when the allocator does,
+    /// this never promotes an inline string to the heap or grows a heap one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotEnoughSpaceError`, leaving the string unchanged, if `ch`
+    /// doesn't fit in the inline buffer or in the spare capacity of the heap
+    /// string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, INLINE_STRING_CAPACITY};
+    ///
+    /// let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 1));
+    /// assert!(s.push_within_capacity('b').is_ok());
+    /// assert!(s.push_within_capacity('c').is_err());
+    /// assert!(s.is_inline());
+    /// ```
+    #[inline]
+    pub fn push_within_capacity(&mut self, ch: char) -> Result<(), NotEnoughSpaceError> {
+        match *self {
+            InlinableString::Heap(ref mut s) => {
+                if s.capacity() - s.len() < ch.len_utf8() {
+                    return Err(NotEnoughSpaceError);
+                }
+                s.push(ch);
+                Ok(())
+            }
+            InlinableString::Inline(ref mut s) => s.push(ch),
+        }
+    }
+
+    /// Appends the given string slice onto the end of this string without
+    /// ever allocating.
+    ///
+    /// Unlike `StringExt::try_push_str`, which only fails when the allocator
+    /// does, this never promotes an inline string to the heap or grows a heap
+    /// one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotEnoughSpaceError`, leaving the string unchanged, if
+    /// `string` doesn't fit in the inline buffer or in the spare capacity of
+    /// the heap string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::with_capacity(100);
+    /// assert!(s.push_str_within_capacity("fits in the existing allocation").is_ok());
+    /// assert!(s.push_str_within_capacity(&"x".repeat(100)).is_err());
+    /// assert_eq!(s, "fits in the existing allocation");
+    /// ```
+    #[inline]
+    pub fn push_str_within_capacity(&mut self, string: &str) -> Result<(), NotEnoughSpaceError> {
+        match *self {
+            InlinableString::Heap(ref mut s) => {
+                if s.capacity() - s.len() < string.len() {
+                    return Err(NotEnoughSpaceError);
+                }
+                s.push_str(string);
+                Ok(())
+            }
+            InlinableString::Inline(ref mut s) => s.push_str(string),
+        }
+    }
+}
+
+/// Convert an `InlinableString` into an `InlineString` if it fits, see
+/// `InlinableString::try_into_inline`.
+impl TryFrom<InlinableString> for InlineString {
+    type Error = InlinableString;
+
+    #[inline]
+    fn try_from(string: InlinableString) -> Result<InlineString, InlinableString> {
+        string.try_into_inline()
+    }
+}
+
+impl fmt::Write for InlinableString {
+    fn write_char(&mut self, ch: char) -> Result<(), fmt::Error> {
+        self.push(ch);
+        Ok(())
+    }
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl ops::Index<ops::Range<usize>> for InlinableString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::Range<usize>) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.index(index),
+            InlinableString::Inline(ref s) => s.index(index),
+        }
+    }
+}
+
+impl ops::Index<ops::RangeTo<usize>> for InlinableString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeTo<usize>) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.index(index),
+            InlinableString::Inline(ref s) => s.index(index),
+        }
+    }
+}
+
+impl ops::Index<ops::RangeFrom<usize>> for InlinableString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeFrom<usize>) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.index(index),
+            InlinableString::Inline(ref s) => s.index(index),
+        }
+    }
+}
+
+impl ops::Index<ops::RangeFull> for InlinableString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeFull) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.index(index),
+            InlinableString::Inline(ref s) => s.index(index),
+        }
+    }
+}
+
+impl ops::Index<ops::RangeInclusive<usize>> for InlinableString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeInclusive<usize>) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.index(index),
+            InlinableString::Inline(ref s) => s.index(index),
+        }
+    }
+}
+
+impl ops::Index<ops::RangeToInclusive<usize>> for InlinableString {
+    type Output = str;
+
+    #[inline]
+    fn index(&self, index: ops::RangeToInclusive<usize>) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.index(index),
+            InlinableString::Inline(ref s) => s.index(index),
+        }
+    }
+}
+
+impl ops::IndexMut<ops::Range<usize>> for InlinableString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::Range<usize>) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.index_mut(index),
+            InlinableString::Inline(ref mut s) => s.index_mut(index),
+        }
+    }
+}
+
+impl ops::IndexMut<ops::RangeTo<usize>> for InlinableString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeTo<usize>) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.index_mut(index),
+            InlinableString::Inline(ref mut s) => s.index_mut(index),
+        }
+    }
+}
+
+impl ops::IndexMut<ops::RangeFrom<usize>> for InlinableString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeFrom<usize>) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.index_mut(index),
+            InlinableString::Inline(ref mut s) => s.index_mut(index),
+        }
+    }
+}
+
+impl ops::IndexMut<ops::RangeFull> for InlinableString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeFull) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.index_mut(index),
+            InlinableString::Inline(ref mut s) => s.index_mut(index),
+        }
+    }
+}
+
+impl ops::IndexMut<ops::RangeInclusive<usize>> for InlinableString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeInclusive<usize>) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.index_mut(index),
+            InlinableString::Inline(ref mut s) => s.index_mut(index),
+        }
+    }
+}
+
+impl ops::IndexMut<ops::RangeToInclusive<usize>> for InlinableString {
+    #[inline]
+    fn index_mut(&mut self, index: ops::RangeToInclusive<usize>) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.index_mut(index),
+            InlinableString::Inline(ref mut s) => s.index_mut(index),
+        }
+    }
+}
+
+impl ops::Deref for InlinableString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.deref(),
+            InlinableString::Inline(ref s) => s.deref(),
+        }
+    }
+}
+
+impl ops::DerefMut for InlinableString {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.deref_mut(),
+            InlinableString::Inline(ref mut s) => s.deref_mut(),
+        }
+    }
+}
+
+impl PartialEq<InlinableString> for InlinableString {
+    #[inline]
+    fn eq(&self, rhs: &InlinableString) -> bool {
+        PartialEq::eq(&self[..], &rhs[..])
+    }
+
+    #[inline]
+    fn ne(&self, rhs: &InlinableString) -> bool {
+        PartialEq::ne(&self[..], &rhs[..])
+    }
+}
+
+macro_rules! impl_eq {
+    ($lhs:ty, $rhs: ty) => {
+        impl<'a> PartialEq<$rhs> for $lhs {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool { PartialEq::eq(&self[..], &other[..]) }
+            #[inline]
+            fn ne(&self, other: &$rhs) -> bool { PartialEq::ne(&self[..], &other[..]) }
+        }
+
+        impl<'a> PartialEq<$lhs> for $rhs {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool { PartialEq::eq(&self[..], &other[..]) }
+            #[inline]
+            fn ne(&self, other: &$lhs) -> bool { PartialEq::ne(&self[..], &other[..]) }
+        }
+
+    }
+}
+
+impl_eq! { InlinableString, str }
+impl_eq! { InlinableString, String }
+impl_eq! { InlinableString, &'a str }
+impl_eq! { InlinableString, InlineString }
+impl_eq! { Cow<'a, str>, InlinableString }
+impl_eq! { InlinableString, Box<str> }
+
+// `OsStr` and `Path` can't be indexed into `str`, so these compare as `$as`
+// instead. `Path`s compare as `OsStr` too, so that equality is byte for byte
+// like `str`'s rather than `Path`'s component-wise one, under which `"a/b/"`
+// and `"a//b"` would both equal `Path::new("a/b")`.
+macro_rules! impl_eq_as {
+    ($lhs:ty, $rhs:ty, $as:ty) => {
+        impl<'a> PartialEq<$rhs> for $lhs {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                PartialEq::eq(AsRef::<$as>::as_ref(self), AsRef::<$as>::as_ref(other))
+            }
+        }
+
+        impl<'a> PartialEq<$lhs> for $rhs {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                PartialEq::eq(AsRef::<$as>::as_ref(self), AsRef::<$as>::as_ref(other))
+            }
+        }
+    }
+}
+
+impl_eq_as! { InlinableString, OsStr, OsStr }
+impl_eq_as! { InlinableString, &'a OsStr, OsStr }
+impl_eq_as! { InlinableString, OsString, OsStr }
+impl_eq_as! { InlinableString, Path, OsStr }
+impl_eq_as! { InlinableString, &'a Path, OsStr }
+impl_eq_as! { InlinableString, PathBuf, OsStr }
+
+impl StringExt for InlinableString {
+    #[inline]
+    fn new() -> Self {
+        InlinableString::Inline(InlineString::new())
+    }
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        if capacity <= INLINE_STRING_CAPACITY {
+            InlinableString::Inline(InlineString::new())
+        } else {
+            InlinableString::Heap(String::with_capacity(capacity))
+        }
+    }
+
+    #[inline]
+    fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        if capacity <= INLINE_STRING_CAPACITY {
+            Ok(InlinableString::Inline(InlineString::new()))
+        } else {
+            let mut s = String::new();
+            s.try_reserve_exact(capacity)?;
+            Ok(InlinableString::Heap(s))
+        }
+    }
+
+    #[inline]
+    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error> {
+        if validate_utf8(&vec).is_ok() {
+            Ok(unsafe { InlinableString::from_utf8_unchecked(vec) })
+        } else {
+            String::from_utf8(vec).map(InlinableString::from)
+        }
+    }
+
+    #[inline]
+    fn from_utf8_lossy_owned(vec: Vec<u8>) -> Self {
+        if validate_utf8(&vec).is_ok() {
+            unsafe { InlinableString::from_utf8_unchecked(vec) }
+        } else {
+            InlinableString::from(<String as StringExt>::from_utf8_lossy_owned(vec))
+        }
+    }
+
+    #[inline]
+    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self {
+        InlinableString::Heap(String::from_raw_parts(buf, length, capacity))
+    }
+
+    #[inline]
+    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self {
+        InlinableString::from(String::from_utf8_unchecked(bytes))
+    }
+
+    #[inline]
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            InlinableString::Heap(s) => s.into_bytes(),
+            InlinableString::Inline(s) => Vec::from(&s[..]),
+        }
+    }
+
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> {
+        match self {
+            InlinableString::Heap(s) => s.into_boxed_str(),
+            InlinableString::Inline(s) => Box::from(&s[..]),
+        }
+    }
+
+    #[inline]
+    fn leak(self) -> &'static mut str {
+        match self {
+            InlinableString::Heap(s) => s.leak(),
+            InlinableString::Inline(s) => String::from(&s[..]).leak(),
+        }
+    }
+
+    #[inline]
+    fn push_str(&mut self, string: &str) {
+        let promoted = match *self {
+            InlinableString::Inline(ref mut s) => {
+                if s.push_str(string).is_ok() {
+                    return;
+                }
+                let mut promoted = String::with_capacity(promotion_capacity(s.len() + string.len()));
+                promoted.push_str(&*s);
+                promoted.push_str(string);
+                promoted
+            },
+            InlinableString::Heap(ref mut s) => {
+                s.push_str(string);
+                return;
+            },
+        };
+        mem::swap(self, &mut InlinableString::Heap(promoted));
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        match *self {
+            InlinableString::Heap(ref s) => s.capacity(),
+            InlinableString::Inline(_) => INLINE_STRING_CAPACITY,
+        }
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len().checked_add(additional).expect("capacity overflow");
+                if new_capacity <= INLINE_STRING_CAPACITY {
+                    return;
+                }
+                // Like `String::reserve`, leave room to grow rather than
+                // allocating exactly what was asked for.
+                let mut promoted = String::with_capacity(promotion_capacity(new_capacity));
+                promoted.push_str(&s);
+                promoted
+            },
+            InlinableString::Heap(ref mut s) => {
+                s.reserve(additional);
+                return;
+            },
+        };
+        mem::swap(self, &mut InlinableString::Heap(promoted));
+    }
+
+    #[inline]
+    fn reserve_exact(&mut self, additional: usize) {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len().checked_add(additional).expect("capacity overflow");
+                if new_capacity <= INLINE_STRING_CAPACITY {
+                    return;
+                }
+                let mut promoted = String::with_capacity(new_capacity);
+                promoted.push_str(&s);
+                promoted
+            },
+            InlinableString::Heap(ref mut s) => {
+                s.reserve_exact(additional);
+                return;
+            },
+        };
+        mem::swap(self, &mut InlinableString::Heap(promoted));
+    }
+
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                // Saturate instead of overflowing: a request that large is
+                // rejected by `String::try_reserve` below.
+                let new_capacity = s.len().saturating_add(additional);
+                if new_capacity <= INLINE_STRING_CAPACITY {
+                    return Ok(());
+                }
+                try_promote(s, promotion_capacity(new_capacity), String::try_reserve_exact)?
+            },
+            InlinableString::Heap(ref mut s) => {
+                return s.try_reserve(additional);
+            },
+        };
+        *self = InlinableString::Heap(promoted);
+        Ok(())
+    }
+
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len().saturating_add(additional);
+                if new_capacity <= INLINE_STRING_CAPACITY {
+                    return Ok(());
+                }
+                try_promote(s, new_capacity, String::try_reserve_exact)?
+            },
+            InlinableString::Heap(ref mut s) => {
+                return s.try_reserve_exact(additional);
+            },
+        };
+        *self = InlinableString::Heap(promoted);
+        Ok(())
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        if self.len() <= INLINE_STRING_CAPACITY {
+            let demoted = if let InlinableString::Heap(ref s) = *self {
+                InlineString::from(&s[..])
+            } else {
+                return;
+            };
+            mem::swap(self, &mut InlinableString::Inline(demoted));
+            return;
+        }
+
+        match *self {
+            InlinableString::Heap(ref mut s) => s.shrink_to_fit(),
+            _ => panic!("inlinable_string: internal error: this branch should be unreachable"),
+        };
+    }
+
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        if min_capacity <= INLINE_STRING_CAPACITY && self.len() <= INLINE_STRING_CAPACITY {
+            let demoted = if let InlinableString::Heap(ref s) = *self {
+                InlineString::from(&s[..])
+            } else {
+                return;
+            };
+            *self = InlinableString::Inline(demoted);
+            return;
+        }
+
+        if let InlinableString::Heap(ref mut s) = *self {
+            s.shrink_to(min_capacity);
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, ch: char) {
+        let promoted = match *self {
+            InlinableString::Inline(ref mut s) => {
+                if s.push(ch).is_ok() {
+                    return;
+                }
+
+                let mut promoted = String::with_capacity(promotion_capacity(s.len() + ch.len_utf8()));
+                promoted.push_str(&*s);
+                promoted.push(ch);
+                promoted
+            },
+            InlinableString::Heap(ref mut s) => {
+                s.push(ch);
+                return;
+            },
+        };
+
+        mem::swap(self, &mut InlinableString::Heap(promoted));
+    }
+
+    #[inline]
+    fn extend_from_within<R>(&mut self, src: R) where R: RangeBounds<usize> {
+        let promoted = match *self {
+            InlinableString::Heap(ref mut s) => {
+                s.extend_from_within(src);
+                return;
+            },
+            InlinableString::Inline(ref mut s) => {
+                let (start, end) = char_range(s, src);
+                if s.extend_from_within(start..end).is_ok() {
+                    return;
+                }
+
+                let mut promoted = String::with_capacity(promotion_capacity(s.len() + (end - start)));
+                promoted.push_str(s);
+                promoted.push_str(&s[start..end]);
+                promoted
+            },
+        };
+
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        match *self {
+            InlinableString::Heap(ref s) => s.as_str(),
+            InlinableString::Inline(ref s) => &s[..],
+        }
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        match *self {
+            InlinableString::Heap(ref s) => s.as_bytes(),
+            InlinableString::Inline(ref s) => s.as_bytes(),
+        }
+    }
+
+    #[inline]
+    fn truncate(&mut self, new_len: usize) {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.truncate(new_len),
+            // `String::truncate` is a no-op past the end of the string, while
+            // `InlineString::truncate` panics.
+            InlinableString::Inline(ref mut s) => if new_len <= s.len() {
+                s.truncate(new_len)
+            },
+        };
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        // A heap string keeps its allocation so it can be refilled without
+        // promoting again; `reset_to_inline` is the way to give it back.
+        match *self {
+            InlinableString::Heap(ref mut s) => s.clear(),
+            InlinableString::Inline(ref mut s) => s.clear(),
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<char> {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.pop(),
+            InlinableString::Inline(ref mut s) => s.pop(),
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, idx: usize) -> char {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.remove(idx),
+            InlinableString::Inline(ref mut s) => s.remove(idx),
+        }
+    }
+
+    #[inline]
+    fn insert(&mut self, idx: usize, ch: char) {
+        let promoted = match *self {
+            InlinableString::Heap(ref mut s) => {
+                s.insert(idx, ch);
+                return;
+            },
+            InlinableString::Inline(ref mut s) => {
+                if s.insert(idx, ch).is_ok() {
+                    return;
+                }
+
+                let mut promoted = String::with_capacity(promotion_capacity(s.len() + ch.len_utf8()));
+                promoted.push_str(&s[..idx]);
+                promoted.push(ch);
+                promoted.push_str(&s[idx..]);
+                promoted
+            },
+        };
+
+        mem::swap(self, &mut InlinableString::Heap(promoted));
+    }
+
+    #[inline]
+    fn insert_str(&mut self, idx: usize, string: &str) {
+        let promoted = match *self {
+            InlinableString::Heap(ref mut s) => {
+                s.insert_str(idx, string);
+                return;
+            },
+            InlinableString::Inline(ref mut s) => {
+                if s.insert_str(idx, string).is_ok() {
+                    return;
+                }
+
+                let mut promoted = String::with_capacity(promotion_capacity(s.len() + string.len()));
+                promoted.push_str(&s[..idx]);
+                promoted.push_str(string);
+                promoted.push_str(&s[idx..]);
+                promoted
+            },
+        };
+
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        let (other, demoted) = match *self {
+            InlinableString::Inline(ref mut s) => {
+                return InlinableString::Inline(s.split_off(at));
+            },
+            InlinableString::Heap(ref mut s) => {
+                assert!(s.is_char_boundary(at));
+
+                let other = if s.len() - at <= INLINE_STRING_CAPACITY {
+                    let other = InlineString::from(&s[at..]);
+                    s.truncate(at);
+                    InlinableString::Inline(other)
+                } else {
+                    InlinableString::Heap(s.split_off(at))
+                };
+
+                if at > INLINE_STRING_CAPACITY {
+                    return other;
+                }
+                (other, InlineString::from(&s[..]))
+            },
+        };
+
+        *self = InlinableString::Inline(demoted);
+        other
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F) where F: FnMut(char) -> bool {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.retain(f),
+            InlinableString::Inline(ref mut s) => s.retain(f),
+        }
+    }
+
+    #[inline]
+    fn replace_range<R>(&mut self, range: R, replace_with: &str) where R: RangeBounds<usize> {
+        let promoted = match *self {
+            InlinableString::Heap(ref mut s) => {
+                s.replace_range(range, replace_with);
+                return;
+            },
+            InlinableString::Inline(ref mut s) => {
+                let (start, end) = char_range(s, range);
+                if s.replace_range(start..end, replace_with).is_ok() {
+                    return;
+                }
+
+                let new_length = s.len() - (end - start) + replace_with.len();
+                let mut promoted = String::with_capacity(promotion_capacity(new_length));
+                promoted.push_str(&s[..start]);
+                promoted.push_str(replace_with);
+                promoted.push_str(&s[end..]);
+                promoted
+            },
+        };
+
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        match *self {
+            InlinableString::Heap(ref mut s) => &mut s.as_mut_vec()[..],
+            InlinableString::Inline(ref mut s) => s.as_mut_slice(),
+        }
+    }
+
+    #[inline]
+    fn as_mut_str(&mut self) -> &mut str {
+        InlinableString::as_mut_str(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        match *self {
+            InlinableString::Heap(ref s) => s.len(),
+            InlinableString::Inline(ref s) => s.len(),
+        }
+    }
+}
+
+/// Joins `parts` into a single `InlinableString`, placing `sep` between each
+/// pair of adjacent parts.
+///
+/// The length of the result is computed first, so it is either stored inline
+/// or allocated on the heap exactly once, and each part is copied exactly once.
+///
+/// # Examples
+///
+/// ```
+/// let s = inlinable_string::join(&["a", "b", "c"], "-");
+/// assert_eq!(s, "a-b-c");
+/// assert!(s.is_inline());
+/// ```
+pub fn join<S: AsRef<str>>(parts: &[S], sep: &str) -> InlinableString {
+    let len = sep.len()
+        .checked_mul(parts.len().saturating_sub(1))
+        .and_then(|len| parts.iter().try_fold(len, |len, part| len.checked_add(part.as_ref().len())))
+        .expect("capacity overflow");
+
+    let mut s = if len <= INLINE_STRING_CAPACITY {
+        InlinableString::new()
+    } else {
+        InlinableString::Heap(String::with_capacity(len))
+    };
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            s.push_str(sep);
+        }
+        s.push_str(part.as_ref());
+    }
+    s
+}
+
+/// Concatenates `parts` into a single `InlinableString`.
+///
+/// Like [`join`](./fn.join.html) with an empty separator, the result is
+/// stored inline or allocated on the heap exactly once.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::InlinableString;
+///
+/// let parts = [InlinableString::from("foo"), InlinableString::from("bar")];
+/// assert_eq!(inlinable_string::concat(&parts), "foobar");
+/// ```
+pub fn concat<S: AsRef<str>>(parts: &[S]) -> InlinableString {
+    join(parts, "")
+}
+
+/// Validates `v` as UTF-8 with `simdutf8`. Its fast validator doesn't say
+/// where the input went wrong, so invalid input is re-checked with
+/// `str::from_utf8` to get the standard library's error.
+#[cfg(feature = "simd-utf8")]
+#[inline]
+fn validate_utf8(v: &[u8]) -> Result<&str, str::Utf8Error> {
+    match simdutf8::basic::from_utf8(v) {
+        Ok(s) => Ok(s),
+        Err(_) => str::from_utf8(v),
+    }
+}
+
+/// Validates `v` as UTF-8.
+#[cfg(not(feature = "simd-utf8"))]
+#[inline]
+fn validate_utf8(v: &[u8]) -> Result<&str, str::Utf8Error> {
+    str::from_utf8(v)
+}
+
+/// Appends each string in `iter` to `s`, for the `Extend` and `FromIterator`
+/// impls over strings.
+fn push_strs<I>(s: &mut InlinableString, iter: I)
+    where I: IntoIterator,
+          I::Item: AsRef<str>
+{
+    // The item count says nothing about the bytes needed, and the items can
+    // only be walked once, so there is nothing useful to reserve up front;
+    // `push_str` grows as needed, and promotes an inline string only once the
+    // contents no longer fit. `extend_from_slice` sums the lengths first.
+    for item in iter {
+        s.push_str(item.as_ref());
+    }
+}
+
+/// Appends `other` to `s` for the `Add` impls. The result stays inline if it
+/// fits; otherwise an inline `s` is promoted with exactly the combined length.
+#[inline]
+fn add_str(s: InlinableString, other: &str) -> InlinableString {
+    match s {
+        InlinableString::Inline(ref inline) if inline.len() + other.len() > INLINE_STRING_CAPACITY => {
+            let mut promoted = String::with_capacity(inline.len() + other.len());
+            promoted.push_str(inline);
+            promoted.push_str(other);
+            InlinableString::Heap(promoted)
+        },
+        mut s => {
+            s.push_str(other);
+            s
+        },
+    }
+}
+
+/// Replaces every match of `from` in `bytes` with `to`, moving the rest of
+/// the contents towards the front, and returns the new length. The bytes past
+/// the new length are zeroed so the buffer as a whole stays valid UTF-8.
+///
+/// Unsafe because `bytes` must be valid UTF-8. `from` must be non-empty and no
+/// shorter than `to`, so that writes never pass the unread input.
+unsafe fn replace_shrinking(bytes: &mut [u8], from: &str, to: &str) -> usize {
+    debug_assert!(!from.is_empty() && to.len() <= from.len());
+
+    let len = bytes.len();
+    let mut read = 0;
+    let mut write = 0;
+    while let Some(pos) = str::from_utf8_unchecked(&bytes[read..]).find(from) {
+        bytes.copy_within(read..read + pos, write);
+        write += pos;
+        bytes[write..write + to.len()].copy_from_slice(to.as_bytes());
+        write += to.len();
+        read += pos + from.len();
+    }
+    bytes.copy_within(read..len, write);
+    write += len - read;
+    for b in &mut bytes[write..] {
+        *b = 0;
+    }
+    write
+}
+
+/// Case-maps each char of `s` with `map`, overwriting it in place. Returns
+/// false, leaving `s` unchanged, if any char maps to a different number of
+/// bytes than it started with.
+fn case_map_in_place<F, I>(s: &mut str, map: F) -> bool
+    where F: Fn(char) -> I,
+          I: Iterator<Item=char>
+{
+    if !s.chars().all(|c| map(c).map(char::len_utf8).sum::<usize>() == c.len_utf8()) {
+        return false;
+    }
+
+    let mut i = 0;
+    while let Some(c) = s[i..].chars().next() {
+        // Each mapping is the same length as `c`, so it exactly overwrites
+        // `c` and the bytes after it are untouched.
+        let bytes = unsafe { s.as_bytes_mut() };
+        for mapped in map(c) {
+            i += mapped.encode_utf8(&mut bytes[i..]).len();
+        }
+    }
+    true
+}
+
+/// The capacity to allocate when promoting an inline string that needs room
+/// for `needed` bytes. Leaving headroom past the inline capacity means a
+/// string that grows one char at a time across the boundary doesn't
+/// reallocate again on every following push.
+#[inline]
+fn promotion_capacity(needed: usize) -> usize {
+    cmp::max(needed, 2 * INLINE_STRING_CAPACITY)
+}
+
+/// Copies `s` into a new heap string, using `reserve` to fallibly allocate
+/// room for `capacity` bytes first. On error nothing has been copied and the
+/// caller's string is untouched.
+#[inline]
+fn try_promote<F>(s: &InlineString, capacity: usize, reserve: F)
+                  -> Result<String, TryReserveError>
+    where F: FnOnce(&mut String, usize) -> Result<(), TryReserveError>
+{
+    let mut promoted = String::new();
+    reserve(&mut promoted, capacity)?;
+    promoted.push_str(s);
+    Ok(promoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InlinableString, InlineString, StringExt, INLINE_STRING_CAPACITY};
+    use std::borrow::Cow;
+    use std::mem;
+    use std::cmp::Ordering;
+    use std::iter::FromIterator;
+
+    #[test]
+    fn test_is_inline() {
+        assert_eq!(InlinableString::INLINE_CAPACITY, INLINE_STRING_CAPACITY);
+
+        let under = "a".repeat(INLINE_STRING_CAPACITY - 1);
+        let s = InlinableString::from(under.as_str());
+        assert!(s.is_inline());
+        assert!(!s.is_heap());
+
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY).as_str());
+        assert!(s.is_inline());
+        assert!(!s.is_heap());
+
+        s.push('a');
+        assert!(!s.is_inline());
+        assert!(s.is_heap());
+
+        s.pop();
+        assert!(s.is_heap());
+        s.shrink_to_fit();
+        assert!(s.is_inline());
+        assert!(!s.is_heap());
+
+        let s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY + 1).as_str());
+        assert!(s.is_heap());
+    }
+
+    #[test]
+    fn test_variant_accessors() {
+        let mut s = InlinableString::from("small");
+        assert_eq!(s.as_inline().unwrap(), "small");
+        assert!(s.as_heap_string().is_none());
+        assert!(s.as_mut_heap_string().is_none());
+
+        s.push_str(&"a".repeat(INLINE_STRING_CAPACITY));
+        assert!(s.as_inline().is_none());
+        assert_eq!(s.as_heap_string().unwrap().len(), INLINE_STRING_CAPACITY + 5);
+
+        unsafe {
+            s.as_mut_heap_string().unwrap().as_mut_vec().truncate(5);
+        }
+        assert_eq!(s, "small");
+        assert!(s.as_inline().is_none());
+        assert_eq!(s.as_heap_string().unwrap(), "small");
+
+        s.shrink_to_fit();
+        assert_eq!(s.as_inline().unwrap(), "small");
+        assert!(s.as_heap_string().is_none());
+        assert!(s.as_mut_heap_string().is_none());
+    }
+
+    #[test]
+    fn test_heap_allocated_bytes() {
+        let size = mem::size_of::<InlinableString>();
+
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY).as_str());
+        assert_eq!(s.heap_allocated_bytes(), 0);
+        assert_eq!(s.total_size_bytes(), size);
+
+        s.push('a');
+        let capacity = s.as_heap_string().unwrap().capacity();
+        assert!(capacity > INLINE_STRING_CAPACITY);
+        assert_eq!(s.heap_allocated_bytes(), capacity);
+        assert_eq!(s.total_size_bytes(), size + capacity);
+
+        s.reserve_exact(100);
+        assert_eq!(s.heap_allocated_bytes(), s.as_heap_string().unwrap().capacity());
+
+        s.truncate(3);
+        s.shrink_to_fit();
+        assert_eq!(s.heap_allocated_bytes(), 0);
+        assert_eq!(s.total_size_bytes(), size);
+    }
+
+    #[test]
+    fn test_into_string() {
+        let s = InlinableString::from("small");
+        let t = s.into_string();
+        assert_eq!(t, "small");
+
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str("a string that is too long to be stored inline");
+        let ptr = s.as_ptr();
+        let capacity = s.capacity();
+        let t = s.into_string();
+        assert_eq!(t, "a string that is too long to be stored inline");
+        assert_eq!(t.as_ptr(), ptr);
+        assert_eq!(t.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_try_into_inline() {
+        use std::convert::TryFrom;
+
+        let s = InlinableString::from("small");
+        assert_eq!(s.try_into_inline().unwrap(), "small");
+
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str("short");
+        assert!(s.is_heap());
+        assert_eq!(InlineString::try_from(s).unwrap(), "short");
+
+        let exact = "a".repeat(INLINE_STRING_CAPACITY);
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str(&exact);
+        assert_eq!(s.try_into_inline().unwrap(), exact.as_str());
+
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str(&exact);
+        s.push('a');
+        let ptr = s.as_ptr();
+        let capacity = s.capacity();
+        let err = InlineString::try_from(s).unwrap_err();
+        assert!(err.is_heap());
+        assert_eq!(err.as_ptr(), ptr);
+        assert_eq!(err.capacity(), capacity);
+        assert_eq!(err.len(), INLINE_STRING_CAPACITY + 1);
+    }
+
+    #[test]
+    fn test_from_utf8_lossy_inlined() {
+        let s = InlinableString::from_utf8_lossy_inlined(b"Hello \xF0\x90\x80World");
+        assert_eq!(s, "Hello \u{FFFD}World");
+        assert!(s.is_inline());
+
+        let valid = "a".repeat(INLINE_STRING_CAPACITY);
+        let s = InlinableString::from_utf8_lossy_inlined(valid.as_bytes());
+        assert_eq!(s, valid.as_str());
+        assert!(s.is_inline());
+
+        let valid = "a".repeat(INLINE_STRING_CAPACITY + 1);
+        let s = InlinableString::from_utf8_lossy_inlined(valid.as_bytes());
+        assert_eq!(s, valid.as_str());
+        assert!(s.is_heap());
+
+        // Substitution can push a short input over the inline capacity.
+        let invalid = vec![0xFF; INLINE_STRING_CAPACITY / 2];
+        let s = InlinableString::from_utf8_lossy_inlined(&invalid);
+        assert_eq!(s, "\u{FFFD}".repeat(INLINE_STRING_CAPACITY / 2));
+        assert!(s.is_heap());
+
+        // The `StringExt` constructor keeps its `Cow` return type.
+        let cow = InlinableString::from_utf8_lossy(b"abc");
+        assert!(matches!(cow, Cow::Borrowed("abc")));
+    }
+
+    #[test]
+    fn test_from_utf8_slice() {
+        let s = InlinableString::from_utf8_slice(b"").unwrap();
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_utf8_slice("héllo".as_bytes()).unwrap();
+        assert_eq!(s, "héllo");
+        assert!(s.is_inline());
+
+        let long = "a string that is too long to be stored inline";
+        let s = InlinableString::from_utf8_slice(long.as_bytes()).unwrap();
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+
+        let err = InlinableString::from_utf8_slice(b"ab\xF0\x90\x80").unwrap_err();
+        assert_eq!(err.valid_up_to(), 2);
+        assert_eq!(err.error_len(), None);
+        let err = InlinableString::from_utf8_slice(b"a\xFFb").unwrap_err();
+        assert_eq!(err.valid_up_to(), 1);
+        assert_eq!(err.error_len(), Some(1));
+    }
+
+    #[test]
+    fn test_utf8_validation_matches_std() {
+        let long_valid = "αβγ 𝄞 abc ".repeat(20);
+        let mut long_invalid = long_valid.clone().into_bytes();
+        long_invalid[150] = 0xFF;
+        let samples: Vec<&[u8]> = vec![
+            b"",
+            b"hello",
+            "héllo 𝄞".as_bytes(),
+            long_valid.as_bytes(),
+            &long_invalid,
+            b"\xFF",
+            b"ab\xF0\x90\x80",
+            b"\xC0\x80",
+            b"\xED\xA0\x80",
+            b"\xF4\x90\x80\x80",
+            b"a\xE2\x82",
+        ];
+
+        for sample in samples {
+            let expected = std::str::from_utf8(sample);
+
+            let slice = InlinableString::from_utf8_slice(sample);
+            assert_eq!(slice.as_ref().map(|s| &s[..]), expected.as_ref().map(|s| *s));
+
+            let owned = <InlinableString as StringExt>::from_utf8(sample.to_vec());
+            match (owned, &expected) {
+                (Ok(s), Ok(e)) => assert_eq!(s, *e),
+                (Err(err), Err(e)) => assert_eq!(err.utf8_error(), *e),
+                (got, _) => panic!("{:?} disagrees with std for {:?}", got, sample),
+            }
+
+            let lossy = String::from_utf8_lossy(sample);
+            assert_eq!(InlinableString::from_utf8_lossy_inlined(sample), lossy);
+            assert_eq!(InlinableString::from_utf8_lossy_owned(sample.to_vec()), lossy);
+        }
+    }
+
+    #[test]
+    fn test_from_ascii() {
+        let s = InlinableString::from_ascii(b"").unwrap();
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_ascii(b"hello").unwrap();
+        assert_eq!(s, "hello");
+        assert!(s.is_inline());
+
+        let long = "a string that is too long to be stored inline";
+        let s = InlinableString::from_ascii(long.as_bytes()).unwrap();
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+        assert_eq!(s.capacity(), long.len());
+
+        let err = InlinableString::from_ascii(b"\x80").unwrap_err();
+        assert_eq!(err.index(), 0);
+        let err = InlinableString::from_ascii("abcé".as_bytes()).unwrap_err();
+        assert_eq!(err.index(), 3);
+        let err = InlinableString::from_ascii(b"ab\xFFcd\xFF").unwrap_err();
+        assert_eq!(err.index(), 2);
+        assert_eq!(err.to_string(), "non-ASCII byte at index 2");
+
+        let s = unsafe { InlinableString::from_ascii_unchecked(b"abc") };
+        assert_eq!(s, "abc");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_size() {
+        use std::mem::size_of;
+        assert_eq!(size_of::<InlinableString>(), 4 * size_of::<usize>());
+    }
+
+    // First, specifically test operations that overflow InlineString's capacity
+    // and require promoting the string to heap allocation.
+
+    #[test]
+    fn test_push_str() {
+        let mut s = InlinableString::new();
+        s.push_str("small");
+        assert_eq!(s, "small");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        s.push_str(long_str);
+        assert_eq!(s, String::from("small") + long_str);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut s = InlinableString::new();
+        write!(&mut s, "small").expect("!write");
+        assert_eq!(s, "small");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        write!(&mut s, "{}", long_str).expect("!write");
+        assert_eq!(s, String::from("small") + long_str);
+    }
+
+    #[test]
+    fn test_write_fmt() {
+        let mut s = InlinableString::new();
+        write!(s, "{} {:.2} {:>4}", 42, 1.5f64, -7).unwrap();
+        assert_eq!(s, "42 1.50   -7");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        write!(s, "{:0>1$}", 0, INLINE_STRING_CAPACITY).unwrap();
+        assert_eq!(s.len(), 12 + INLINE_STRING_CAPACITY);
+        assert!(InlinableString::capacity(&s) > INLINE_STRING_CAPACITY);
+        assert!(s.ends_with("000"));
+
+        fn describe<S: StringExt>(out: &mut S, n: u32) {
+            use StringExtWrite;
+            write!(out, "n={}", n).unwrap();
+            writeln!(out, ", n²={}", n * n).unwrap();
+        }
+
+        let mut s = InlinableString::new();
+        describe(&mut s, 12);
+        assert_eq!(s, "n=12, n²=144\n");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let mut s = String::new();
+        describe(&mut s, 3);
+        assert_eq!(s, "n=3, n²=9\n");
+    }
+
+    #[test]
+    fn test_dyn_string_ext() {
+        fn shout(string: &mut dyn StringExt) {
+            string.as_mut_str().make_ascii_uppercase();
+            string.push('!');
+            string.insert_str(0, ">> ");
+        }
+
+        let mut s = InlinableString::from("hello");
+        shout(&mut s);
+        assert_eq!(s, ">> HELLO!");
+
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        shout(&mut s);
+        assert_eq!(s, ">> A STRING THAT IS TOO LONG TO BE STORED INLINE!");
+
+        let mut s = String::from("hello");
+        shout(&mut s);
+        assert_eq!(s, ">> HELLO!");
+    }
+
+    #[test]
+    fn test_generic_str_methods() {
+        fn tidy<S: StringExt>(string: &mut S) -> usize {
+            let trimmed = string.trim().len();
+            if string.starts_with(' ') {
+                let start = string.len() - string.trim_start().len();
+                string.drain(..start);
+            }
+            string.truncate(trimmed);
+            if !string.contains('.') {
+                string.push('.');
+            }
+            string.chars().count()
+        }
+
+        let mut s = InlinableString::from("  héllo ");
+        assert_eq!(tidy(&mut s), 6);
+        assert_eq!(s, "héllo.");
+        assert!(s.ends_with('.'));
+
+        let mut s = String::from("  héllo ");
+        assert_eq!(tidy(&mut s), 6);
+        assert_eq!(s, "héllo.");
+    }
+
+    #[test]
+    fn test_push() {
+        let mut s = InlinableString::new();
+
+        for _ in 0..INLINE_STRING_CAPACITY {
+            s.push('a');
+        }
+        s.push('a');
+
+        assert_eq!(s, String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a')));
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut s = InlinableString::new();
+
+        for _ in 0..INLINE_STRING_CAPACITY {
+            s.insert(0, 'a');
+        }
+        s.insert(0, 'a');
+
+        assert_eq!(s, String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a')));
+    }
+
+    #[test]
+    fn test_insert_str() {
+        let mut s = InlinableString::from("bar");
+        s.insert_str(0, "foo");
+        assert_eq!(s, "foobar");
+        s.insert_str(s.len(), "baz");
+        assert_eq!(s, "foobarbaz");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        s.insert_str(3, long_str);
+        assert_eq!(s, String::from("foo") + long_str + "barbaz");
+        assert_eq!(InlinableString::capacity(&s), s.len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_str_not_char_boundary() {
+        let mut s = InlinableString::from("𝄞");
+        s.insert_str(1, "a");
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut s = InlinableString::from("foobar");
+        let t = s.split_off(3);
+        assert_eq!(s, "foo");
+        assert_eq!(t, "bar");
+
+        let u = s.split_off(s.len());
+        assert_eq!(s, "foo");
+        assert_eq!(u, "");
+    }
+
+    #[test]
+    fn test_split_off_heap_into_inline_halves() {
+        let mut s = InlinableString::from("abcdefghijklmnopqrstuvwxyzabcdefghijklmn");
+        assert_eq!(s.len(), 40);
+        let t = s.split_off(20);
+        assert_eq!(s, "abcdefghijklmnopqrst");
+        assert_eq!(t, "uvwxyzabcdefghijklmn");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        assert_eq!(InlinableString::capacity(&t), INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    fn test_split_off_at_inline_capacity() {
+        let long_str = String::from_iter((0..INLINE_STRING_CAPACITY * 3).map(|_| 'a'));
+
+        let mut s = InlinableString::from(&long_str[..INLINE_STRING_CAPACITY + 1]);
+        let t = s.split_off(INLINE_STRING_CAPACITY);
+        assert_eq!(s, &long_str[..INLINE_STRING_CAPACITY]);
+        assert_eq!(t, "a");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let mut s = InlinableString::from(&long_str[..]);
+        let t = s.split_off(INLINE_STRING_CAPACITY);
+        assert_eq!(s, &long_str[..INLINE_STRING_CAPACITY]);
+        assert_eq!(t, &long_str[INLINE_STRING_CAPACITY..]);
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        assert!(InlinableString::capacity(&t) > INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_not_char_boundary() {
+        let mut s = InlinableString::from("𝄞");
+        s.split_off(1);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut s = InlinableString::from("a𝄞bé_c");
+        s.retain(|c| c.is_ascii());
+        assert_eq!(s, "ab_c");
+        s.retain(|_| true);
+        assert_eq!(s, "ab_c");
+        s.retain(|_| false);
+        assert_eq!(s, "");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        let mut s = InlinableString::from(long_str);
+        s.retain(|c| c != ' ' && c != '\n');
+        assert_eq!(s, String::from_iter(long_str.chars().filter(|&c| c != ' ' && c != '\n')));
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut s = InlinableString::from("a𝄞bcdé");
+        {
+            let mut drain = s.drain(1..8);
+            assert_eq!(drain.next(), Some('𝄞'));
+            assert_eq!(drain.next_back(), Some('d'));
+            assert_eq!(drain.as_str(), "bc");
+        }
+        assert_eq!(s, "aé");
+
+        let t: String = s.drain(..).collect();
+        assert_eq!(t, "aé");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_drain_heap() {
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        let mut s = InlinableString::from(long_str);
+        let t: String = s.drain(4..).rev().collect();
+        assert_eq!(s, "this");
+        assert_eq!(t, String::from_iter(long_str[4..].chars().rev()));
+    }
+
+    #[test]
+    fn test_drain_forget() {
+        let mut s = InlinableString::from("foobar");
+        {
+            let mut drain = s.drain(1..3);
+            assert_eq!(drain.next(), Some('o'));
+            mem::forget(drain);
+        }
+        assert_eq!(s, "foobar");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_not_char_boundary() {
+        let mut s = InlinableString::from("𝄞");
+        s.drain(..1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_out_of_bounds() {
+        let mut s = InlinableString::from("foo");
+        s.drain(..4);
+    }
+
+    #[test]
+    fn test_replace_range() {
+        let mut s = InlinableString::from("foobar");
+        s.replace_range(1..3, "ubar ");
+        assert_eq!(s, "fubar bar");
+        s.replace_range(..=4, "baz");
+        assert_eq!(s, "baz bar");
+        s.replace_range(3.., "");
+        assert_eq!(s, "baz");
+        s.replace_range(.., "quux");
+        assert_eq!(s, "quux");
+    }
+
+    #[test]
+    fn test_replace_range_promotes() {
+        let full = String::from_iter((0..INLINE_STRING_CAPACITY).map(|_| 'a'));
+        let mut s = InlinableString::from(&full[..]);
+        s.replace_range(1..2, "bc");
+        assert_eq!(s, String::from("abc") + &full[2..]);
+        assert!(InlinableString::capacity(&s) > INLINE_STRING_CAPACITY);
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        s.replace_range(.., long_str);
+        assert_eq!(s, long_str);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_replace_range_not_char_boundary() {
+        let mut s = InlinableString::from("𝄞");
+        s.replace_range(..2, "a");
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut s = InlinableString::from("a𝄞b");
+        s.extend_from_within(1..5);
+        assert_eq!(s, "a𝄞b𝄞");
+        s.extend_from_within(..);
+        assert_eq!(s, "a𝄞b𝄞a𝄞b𝄞");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        s.extend_from_within(..);
+        assert_eq!(s, "a𝄞b𝄞a𝄞b𝄞a𝄞b𝄞a𝄞b𝄞");
+        assert!(InlinableString::capacity(&s) > INLINE_STRING_CAPACITY);
+
+        s.extend_from_within(..1);
+        assert_eq!(s, "a𝄞b𝄞a𝄞b𝄞a𝄞b𝄞a𝄞b𝄞a");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_extend_from_within_not_char_boundary() {
+        let mut s = InlinableString::from("a𝄞b");
+        s.extend_from_within(2..);
+    }
+
+    #[test]
+    fn test_as_str() {
+        let short = InlinableString::from("foo");
+        assert_eq!(short.as_str(), &*short);
+
+        let long = InlinableString::from("hello world hello world hello world");
+        assert_eq!(long.as_str(), &*long);
+
+        let dyn_short: &dyn StringExt = &short;
+        assert_eq!(dyn_short.as_str(), "foo");
+        let dyn_long: &dyn StringExt = &long;
+        assert_eq!(dyn_long.as_str(), "hello world hello world hello world");
+    }
+
+    #[test]
+    fn test_as_mut_str() {
+        let mut s = InlinableString::from("héllo");
+        s.as_mut_str().make_ascii_uppercase();
+        assert_eq!(s, "HéLLO");
+        assert_eq!(s.len(), 6);
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        let mut s = InlinableString::from(long_str);
+        s.as_mut_str().make_ascii_uppercase();
+        assert_eq!(s, long_str.to_ascii_uppercase());
+        assert_eq!(s.len(), long_str.len());
+    }
+
+    #[test]
+    fn test_from_string() {
+        let long = String::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let capacity = long.capacity();
+        let s = InlinableString::from(long);
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(InlinableString::capacity(&s), capacity);
+
+        let s = InlinableString::from(String::with_capacity(100) + "small");
+        assert!(s.is_inline());
+        assert_eq!(s, "small");
+
+        let s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY));
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_from_cow() {
+        let long_str = "a string that is too long to be stored inline";
+
+        let s = InlinableString::from(Cow::Borrowed("small"));
+        assert!(s.is_inline());
+        assert_eq!(s, "small");
+
+        let s = InlinableString::from(Cow::Borrowed(long_str));
+        assert!(s.is_heap());
+        assert_eq!(s, long_str);
+
+        let s: InlinableString = Cow::Owned::<str>(String::from("small")).into();
+        assert!(s.is_inline());
+        assert_eq!(s, "small");
+
+        let owned = String::from(long_str);
+        let ptr = owned.as_ptr();
+        let s = InlinableString::from(Cow::Owned::<str>(owned));
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s, long_str);
+    }
+
+    #[test]
+    fn test_from_char() {
+        for &ch in &['a', 'é', '€', '𝄞'] {
+            let s = InlinableString::from(ch);
+            assert!(s.is_inline());
+            assert_eq!(s.len(), ch.len_utf8());
+            assert_eq!(s, String::from(ch));
+            assert_eq!(s, InlinableString::from_iter(Some(ch)));
+        }
+    }
+
+    #[test]
+    fn test_from_inline_string() {
+        let inline = InlineString::from("hello");
+        let s = InlinableString::from(&inline);
+        assert!(s.is_inline());
+        assert_eq!(s, "hello");
+
+        let s: InlinableString = inline.into();
+        assert!(s.is_inline());
+        assert_eq!(s, "hello");
+
+        let full = InlineString::from(&"a".repeat(INLINE_STRING_CAPACITY)[..]);
+        let s = InlinableString::from(full);
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    fn test_into_std_string() {
+        let long_str = "a string that is too long to be stored inline";
+        let s = InlinableString::from(long_str);
+        let ptr = s.as_ptr();
+        let string = String::from(s);
+        assert_eq!(string.as_ptr(), ptr);
+        assert_eq!(string, long_str);
+
+        let string: String = InlinableString::from("small").into();
+        assert_eq!(string, "small");
+
+        fn takes_into_string<T: Into<String>>(t: T) -> String {
+            t.into()
+        }
+        assert_eq!(takes_into_string(InlinableString::from("generic")), "generic");
+    }
+
+    #[test]
+    fn test_into_cow() {
+        fn takes_cow<'a, T: Into<Cow<'a, str>>>(t: T) -> Cow<'a, str> {
+            t.into()
+        }
+
+        let small = InlinableString::from("small");
+        let cow = takes_cow(&small);
+        assert!(matches!(cow, Cow::Borrowed(_)));
+        assert_eq!(cow, "small");
+        assert_eq!(InlinableString::from(cow), small);
+
+        let cow = takes_cow(small.clone());
+        assert!(matches!(cow, Cow::Owned(_)));
+        assert_eq!(InlinableString::from(cow), small);
+
+        let long_str = "a string that is too long to be stored inline";
+        let long = InlinableString::from(long_str);
+        let ptr = long.as_ptr();
+        let cow: Cow<'static, str> = takes_cow(long);
+        assert!(matches!(cow, Cow::Owned(_)));
+        assert_eq!(cow.as_ptr(), ptr);
+        let s = InlinableString::from(cow);
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s, long_str);
+    }
+
+    #[test]
+    fn test_into_vec_u8() {
+        let long_str = "a string that is too long to be stored inline";
+        let s = InlinableString::from(long_str);
+        let ptr = s.as_ptr();
+        let v = Vec::<u8>::from(s);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v, long_str.as_bytes());
+
+        let v: Vec<u8> = InlinableString::from("héllo").into();
+        assert_eq!(v, "héllo".as_bytes());
+
+        let v: Vec<u8> = InlinableString::new().into();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_into_arc_and_rc() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let long_str = "a string that is too long to be stored inline";
+        for &sample in &["", "small", long_str] {
+            let arc = Arc::<str>::from(InlinableString::from(sample));
+            assert_eq!(&*arc, sample);
+            let rc: Rc<str> = InlinableString::from(sample).into();
+            assert_eq!(&*rc, sample);
+        }
+    }
+
+    #[test]
+    fn test_from_arc_and_rc() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let s = InlinableString::from(Arc::<str>::from("héllo"));
+        assert!(s.is_inline());
+        assert_eq!(s, "héllo");
+        let s: InlinableString = Rc::<str>::from("héllo").into();
+        assert!(s.is_inline());
+        assert_eq!(s, "héllo");
+
+        let long_str = "a string that is too long to be stored inline, née heap";
+        let s = InlinableString::from(Arc::<str>::from(long_str));
+        assert!(s.is_heap());
+        assert_eq!(s, long_str);
+        let s = InlinableString::from(Rc::<str>::from(long_str));
+        assert!(s.is_heap());
+        assert_eq!(s, long_str);
+    }
+
+    #[test]
+    fn test_try_from_os_str() {
+        use std::convert::TryFrom;
+        use std::ffi::{OsStr, OsString};
+
+        let s = InlinableString::try_from(OsStr::new("file.txt")).unwrap();
+        assert!(s.is_inline());
+        assert_eq!(s, "file.txt");
+
+        let s = InlinableString::try_from(OsStr::new("fichier-été.txt")).unwrap();
+        assert!(s.is_inline());
+        assert_eq!(s, "fichier-été.txt");
+
+        let long_name = "a file name that is too long to be stored inline.txt";
+        let s = InlinableString::try_from(OsStr::new(long_name)).unwrap();
+        assert!(s.is_heap());
+        assert_eq!(s, long_name);
+
+        let s = InlinableString::try_from(OsString::from("file.txt")).unwrap();
+        assert!(s.is_inline());
+        assert_eq!(s, "file.txt");
+
+        let s = InlinableString::try_from(OsString::from(long_name)).unwrap();
+        assert!(s.is_heap());
+        assert_eq!(s, long_name);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_try_from_os_str_invalid_unicode() {
+        use std::convert::TryFrom;
+        use std::ffi::{OsStr, OsString};
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+        let invalid = OsStr::from_bytes(b"file\xFF.txt");
+        assert_eq!(InlinableString::try_from(invalid), Err(invalid));
+
+        let invalid = OsString::from_vec(b"file\xFF.txt".to_vec());
+        assert_eq!(InlinableString::try_from(invalid.clone()), Err(invalid));
+
+        let long_name = "a file name that is too long to be stored inline.txt";
+        let os_string = OsString::from_vec(long_name.as_bytes().to_vec());
+        let ptr = os_string.as_bytes().as_ptr();
+        let s = InlinableString::try_from(os_string).unwrap();
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_from_os_str_lossy() {
+        use std::ffi::OsStr;
+
+        let s = InlinableString::from_os_str_lossy(OsStr::new("fichier-été.txt"));
+        assert!(s.is_inline());
+        assert_eq!(s, "fichier-été.txt");
+
+        let long_name = "a file name that is too long to be stored inline.txt";
+        let s = InlinableString::from_os_str_lossy(OsStr::new(long_name));
+        assert!(s.is_heap());
+        assert_eq!(s, long_name);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_os_str_lossy_invalid_unicode() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let invalid = OsStr::from_bytes(b"file\xFF\xF0\x90\x80.txt");
+        let s = InlinableString::from_os_str_lossy(invalid);
+        assert!(s.is_inline());
+        assert_eq!(s, "file\u{FFFD}\u{FFFD}.txt");
+        assert_eq!(s, invalid.to_string_lossy());
+
+        let mut bytes = b"a file name that is too long to be stored inline".to_vec();
+        bytes.push(0xFF);
+        let invalid = OsStr::from_bytes(&bytes);
+        let s = InlinableString::from_os_str_lossy(invalid);
+        assert!(s.is_heap());
+        assert!(s.ends_with('\u{FFFD}'));
+        assert_eq!(s, invalid.to_string_lossy());
+    }
+
+    #[test]
+    fn test_os_str_conversions() {
+        use std::ffi::{OsStr, OsString};
+        use std::process::Command;
+
+        let s = InlinableString::from("--verbose");
+        let mut command = Command::new("echo");
+        command.arg(&s).arg(s.clone());
+        assert_eq!(command.get_args().collect::<Vec<_>>(), [OsStr::new("--verbose"); 2]);
+
+        let os_str: &OsStr = s.as_ref();
+        assert_eq!(os_str, "--verbose");
+
+        assert_eq!(OsString::from(InlinableString::from("héllo")), "héllo");
+        let long_str = "a string that is too long to be stored inline";
+        assert_eq!(OsString::from(InlinableString::from(long_str)), long_str);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_into_os_string_reuses_buffer() {
+        use std::ffi::OsString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let s = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = s.as_ptr();
+        let os_string = OsString::from(s);
+        assert_eq!(os_string.as_bytes().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_path_conversions() {
+        use std::path::{Path, PathBuf};
+
+        let dir = InlinableString::from("src");
+        let file = InlinableString::from("lib.rs");
+
+        let mut path = PathBuf::from(dir.clone());
+        path.push(&file);
+        assert_eq!(path, Path::new("src/lib.rs"));
+        assert_eq!(Path::new("src").join(&file), path);
+
+        let as_path: &Path = dir.as_ref();
+        assert_eq!(as_path.join(file), path);
+        assert!(Path::new("src/lib.rs").starts_with(&dir));
+
+        let long_str = "a/path/that/is/too/long/to/be/stored/inline.txt";
+        let path = PathBuf::from(InlinableString::from(long_str));
+        assert_eq!(path, Path::new(long_str));
+        assert_eq!(path.extension().unwrap(), "txt");
+    }
+
+    // Checks `$lhs(x) == $rhs(y)` in both operand orders against `x == y` for
+    // every pair of samples. The `deref` form compares against `*$rhs(y)`,
+    // for unsized right-hand sides such as `str`.
+    macro_rules! check_eq_pair {
+        ($samples:expr, $lhs:expr, $rhs:expr) => {
+            for &x in $samples {
+                for &y in $samples {
+                    let (l, r) = ($lhs(x), $rhs(y));
+                    assert_eq!(l == r, x == y, "{:?} == {:?}", x, y);
+                    assert_eq!(r == l, x == y, "{:?} == {:?}", y, x);
+                }
+            }
+        };
+        (deref $samples:expr, $lhs:expr, $rhs:expr) => {
+            for &x in $samples {
+                for &y in $samples {
+                    let (l, r) = ($lhs(x), $rhs(y));
+                    assert_eq!(l == *r, x == y, "{:?} == {:?}", x, y);
+                    assert_eq!(*r == l, x == y, "{:?} == {:?}", y, x);
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_eq_matrix() {
+        let full = "a".repeat(INLINE_STRING_CAPACITY);
+        let almost_full = format!("{}b", &full[1..]);
+        let long = format!("{}a", full);
+        let short: &[&str] = &["", "a", "é", "b", &full, &almost_full];
+        let all: &[&str] = &["", "a", "é", "b", &full, &almost_full, &long];
+
+        fn inline(s: &str) -> InlinableString { InlinableString::from(s) }
+        fn heap(s: &str) -> InlinableString { InlinableString::from_string_buffer(String::from(s)) }
+        fn inline_string(s: &str) -> InlineString { InlineString::from(s) }
+        fn string(s: &str) -> String { String::from(s) }
+        fn cow_borrowed(s: &str) -> Cow<'_, str> { Cow::Borrowed(s) }
+        fn cow_owned(s: &str) -> Cow<'_, str> { Cow::Owned(String::from(s)) }
+        fn boxed(s: &str) -> Box<str> { Box::from(s) }
+        fn str_ref(s: &str) -> &str { s }
+
+        // `InlinableString` compares by content whatever its storage.
+        check_eq_pair!(all, inline, inline);
+        check_eq_pair!(all, inline, heap);
+        check_eq_pair!(all, heap, heap);
+
+        for &lhs in &[inline as fn(&str) -> InlinableString, heap] {
+            check_eq_pair!(short, lhs, inline_string);
+            check_eq_pair!(all, lhs, string);
+            check_eq_pair!(all, lhs, str_ref);
+            check_eq_pair!(deref all, lhs, str_ref);
+            check_eq_pair!(all, lhs, cow_borrowed);
+            check_eq_pair!(all, lhs, cow_owned);
+            check_eq_pair!(all, lhs, boxed);
+        }
+
+        check_eq_pair!(short, inline_string, inline_string);
+        check_eq_pair!(short, inline_string, string);
+        check_eq_pair!(short, inline_string, str_ref);
+        check_eq_pair!(deref short, inline_string, str_ref);
+        check_eq_pair!(short, inline_string, cow_borrowed);
+        check_eq_pair!(short, inline_string, cow_owned);
+        check_eq_pair!(short, inline_string, boxed);
+    }
+
+    #[test]
+    fn test_eq_os_str_and_path() {
+        use std::ffi::{OsStr, OsString};
+        use std::path::{Path, PathBuf};
+
+        let long_str = "a/path/that/is/too/long/to/be/stored/inline/été";
+        let other_os_string = OsString::from("other");
+        let other_path_buf = PathBuf::from("other");
+        for &sample in &["", "été", long_str] {
+            let s = InlinableString::from(sample);
+            let os_str = OsStr::new(sample);
+            let os_string = OsString::from(sample);
+            let path = Path::new(sample);
+            let path_buf = PathBuf::from(sample);
+
+            assert_eq!(s, *os_str);
+            assert_eq!(*os_str, s);
+            assert_eq!(s, os_str);
+            assert_eq!(os_str, s);
+            assert_eq!(s, os_string);
+            assert_eq!(os_string, s);
+            assert_eq!(s, *path);
+            assert_eq!(*path, s);
+            assert_eq!(s, path);
+            assert_eq!(path, s);
+            assert_eq!(s, path_buf);
+            assert_eq!(path_buf, s);
+
+            assert_ne!(s, OsStr::new("other"));
+            assert_ne!(OsStr::new("other"), s);
+            assert_ne!(s, other_os_string);
+            assert_ne!(other_os_string, s);
+            assert_ne!(s, Path::new("other"));
+            assert_ne!(Path::new("other"), s);
+            assert_ne!(s, other_path_buf);
+            assert_ne!(other_path_buf, s);
+        }
+
+        // Paths compare by bytes, not by components.
+        for &sample in &["a/b/", "a//b", "a/./b", "a/b/."] {
+            let s = InlinableString::from(sample);
+            assert_ne!(s, *Path::new("a/b"));
+            assert_ne!(*Path::new("a/b"), s);
+            assert_ne!(s, PathBuf::from("a/b"));
+            assert_ne!(PathBuf::from("a/b"), s);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_eq_invalid_unicode_os_str() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        use std::path::Path;
+
+        let invalid = OsStr::from_bytes(b"file\xFF");
+        let s = InlinableString::from("file\u{FFFD}");
+        assert_ne!(s, invalid);
+        assert_ne!(invalid, s);
+        assert_ne!(s, Path::new(invalid));
+        assert_ne!(Path::new(invalid), s);
+        assert_ne!(InlinableString::from("file"), invalid);
+    }
+
+    #[test]
+    fn test_into_boxed_error() {
+        use std::error::Error;
+
+        fn fails(message: InlinableString) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err(message.into())
+        }
+
+        let long_str = "a message that is too long to be stored inline";
+        for &message in &["bad input", long_str] {
+            let err = fails(InlinableString::from(message)).unwrap_err();
+            assert_eq!(err.to_string(), message);
+            assert_eq!(format!("{:?}", err), format!("{:?}", message));
+
+            let err: Box<dyn Error> = err;
+            assert_eq!(err.to_string(), message);
+
+            let err = Box::<dyn Error>::from(InlinableString::from(message));
+            assert_eq!(err.to_string(), message);
+            assert!(err.source().is_none());
+        }
+    }
+
+    #[test]
+    fn test_to_socket_addrs() {
+        use std::net::{SocketAddr, ToSocketAddrs};
+
+        let addr = InlinableString::from("127.0.0.1:8080");
+        assert!(addr.is_inline());
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs().unwrap().collect();
+        assert_eq!(addrs, ["127.0.0.1:8080".parse::<SocketAddr>().unwrap()]);
+
+        assert!(InlinableString::from("not an address").to_socket_addrs().is_err());
+        assert!(InlinableString::from("127.0.0.1:port").to_socket_addrs().is_err());
+    }
+
+    #[test]
+    fn test_box_str_conversions() {
+        let long_str = "a string that is too long to be stored inline";
+
+        let boxed: Box<str> = Box::from(long_str);
+        let ptr = boxed.as_ptr();
+        let s = InlinableString::from(boxed);
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s, long_str);
+
+        let boxed = Box::<str>::from(s);
+        assert_eq!(boxed.as_ptr(), ptr);
+        assert_eq!(&*boxed, long_str);
+
+        let s = InlinableString::from(Box::<str>::from("small"));
+        assert!(s.is_inline());
+        assert_eq!(&*Box::<str>::from(s), "small");
+
+        let s = InlinableString::from(Box::<str>::from(""));
+        assert!(s.is_inline());
+        assert!(s.is_empty());
+        assert_eq!(&*Box::<str>::from(s), "");
+    }
+
+    #[test]
+    fn test_from_mut_str_and_string_ref() {
+        let mut full = "a".repeat(INLINE_STRING_CAPACITY);
+        let s = InlinableString::from(full.as_mut_str());
+        assert!(s.is_inline());
+        assert_eq!(s, full);
+
+        let s = InlinableString::from(&full);
+        assert!(s.is_inline());
+        assert_eq!(s, full);
+
+        full.push('b');
+        let s = InlinableString::from(full.as_mut_str());
+        assert!(s.is_heap());
+        assert_eq!(s, full);
+
+        // The `String` is only borrowed: its buffer is copied, not taken.
+        let s = InlinableString::from(&full);
+        assert!(s.is_heap());
+        assert_ne!(s.as_ptr(), full.as_ptr());
+        assert_eq!(s, full);
+        full.push('c');
+        assert_eq!(s.len() + 1, full.len());
+    }
+
+    #[test]
+    fn test_from_string_buffer() {
+        let mut buf = String::with_capacity(100);
+        buf.push_str("small");
+        let ptr = buf.as_ptr();
+        let capacity = buf.capacity();
+
+        let mut s = InlinableString::from_string_buffer(buf);
+        assert!(s.is_heap());
+        assert_eq!(s, "small");
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(InlinableString::capacity(&s), capacity);
+
+        s.push_str(" and then some more, growing past the inline capacity");
+        assert_eq!(s.as_ptr(), ptr);
+
+        let s = InlinableString::from_string_buffer(String::new());
+        assert!(s.is_heap());
+        assert!(s.is_empty());
+    }
+
+    /// Wraps an iterator and reports a lower bound of `hint` elements,
+    /// regardless of how many it actually yields.
+    struct OverstatedHint<I> {
+        iter: I,
+        hint: usize,
+    }
+
+    impl<I: Iterator> Iterator for OverstatedHint<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<I::Item> {
+            self.iter.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.hint, None)
+        }
+    }
+
+    #[test]
+    fn test_extend_char_ignores_size_hint() {
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 10));
+        s.extend(OverstatedHint { iter: "0123456789".chars(), hint: 15 });
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::new();
+        s.extend(OverstatedHint {
+            iter: "x".repeat(INLINE_STRING_CAPACITY).chars(),
+            hint: 100,
+        });
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let s: InlinableString = OverstatedHint { iter: "abc".chars(), hint: 50 }.collect();
+        assert_eq!(s, "abc");
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY));
+        s.extend(OverstatedHint { iter: "bc".chars(), hint: 1000 });
+        assert!(s.is_heap());
+        assert_eq!(s, format!("{}bc", "a".repeat(INLINE_STRING_CAPACITY)));
+    }
+
+    #[test]
+    fn test_extend_str_reserves_bytes_not_items() {
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 2));
+        s.extend(vec![""; 40]);
+        s.extend(OverstatedHint { iter: vec!["", "b", "", "c"].into_iter(), hint: 40 });
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let s: InlinableString = vec![""; 100].into_iter().chain(Some("x")).collect();
+        assert_eq!(s, "x");
+        assert!(s.is_inline());
+
+        let piece = "x".repeat(100);
+        let mut s = InlinableString::new();
+        s.extend(vec![&piece[..]; 10]);
+        assert!(s.is_heap());
+        assert_eq!(s, piece.repeat(10));
+    }
+
+    #[test]
+    fn test_extend_string() {
+        let mut s = InlinableString::from("a");
+        s.extend(vec![String::from("b"), String::new(), String::from("c")]);
+        assert_eq!(s, "abc");
+        assert!(s.is_inline());
+
+        s.extend(vec![String::from("0123456789"); 4]);
+        assert!(s.is_heap());
+        assert_eq!(s, format!("abc{}", "0123456789".repeat(4)));
+    }
+
+    #[test]
+    fn test_from_iter_string() {
+        let s: InlinableString = Vec::<String>::new().into_iter().collect();
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+
+        fn collect(n: usize) -> InlinableString {
+            (0..n).map(|_| String::from("ab")).collect()
+        }
+
+        let below = collect(INLINE_STRING_CAPACITY / 2 - 1);
+        assert_eq!(below.len(), INLINE_STRING_CAPACITY - 2);
+        assert!(below.is_inline());
+
+        let at = collect(INLINE_STRING_CAPACITY / 2);
+        assert_eq!(at.len(), INLINE_STRING_CAPACITY);
+        assert!(at.is_inline());
+
+        let above = collect(INLINE_STRING_CAPACITY / 2 + 1);
+        assert_eq!(above.len(), INLINE_STRING_CAPACITY + 2);
+        assert!(above.is_heap());
+        assert_eq!(above, "ab".repeat(INLINE_STRING_CAPACITY / 2 + 1));
+
+        let lines = "one\ntwo\nthree";
+        let s: InlinableString = lines.lines().map(|l| l.to_uppercase()).collect();
+        assert_eq!(s, "ONETWOTHREE");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_extend_cow() {
+        let mut s = InlinableString::new();
+        s.extend(vec![Cow::Borrowed("foo"), Cow::Owned(String::from("bar"))]);
+        assert_eq!(s, "foobar");
+        assert!(s.is_inline());
+
+        s.extend(vec![
+            Cow::Owned(String::from(" and then")),
+            Cow::Borrowed(" some more"),
+            Cow::Borrowed(", past the inline capacity"),
+        ]);
+        assert!(s.is_heap());
+        assert_eq!(s, "foobar and then some more, past the inline capacity");
+    }
+
+    #[test]
+    fn test_from_iter_cow() {
+        let text = "a&b&&c";
+        fn escape(part: &str) -> Cow<'_, str> {
+            if part.is_empty() {
+                Cow::Borrowed("&")
+            } else if part == "b" {
+                Cow::Owned(part.to_uppercase())
+            } else {
+                Cow::Borrowed(part)
+            }
+        }
+        let from_cow: InlinableString = text.split('&').map(escape).collect();
+        let from_str: InlinableString = text.split('&')
+            .map(|p| if p.is_empty() { "&" } else if p == "b" { "B" } else { p })
+            .collect();
+        assert_eq!(from_cow, "aB&c");
+        assert_eq!(from_cow, from_str);
+        assert_eq!(from_cow.is_inline(), from_str.is_inline());
+
+        let s: InlinableString = vec![Cow::Borrowed("a"), Cow::Owned(String::from("b"))]
+            .into_iter().collect();
+        assert_eq!(s, "ab");
+        assert!(s.is_inline());
+
+        let long = "a string that is too long to be stored inline";
+        let s: InlinableString = vec![Cow::Borrowed("> "), Cow::Owned(String::from(long))]
+            .into_iter().collect();
+        assert!(s.is_heap());
+        assert_eq!(s, format!("> {}", long));
+    }
+
+    #[test]
+    fn test_extend_box_str() {
+        let mut s = InlinableString::from("foo");
+        s.extend(Vec::<Box<str>>::new());
+        assert_eq!(s, "foo");
+        assert!(s.is_inline());
+
+        let s: InlinableString = Vec::<Box<str>>::new().into_iter().collect();
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+
+        let long = "a single item that is too long to be stored inline";
+        let s: InlinableString = vec![Box::<str>::from(long)].into_iter().collect();
+        assert!(s.is_heap());
+        assert_eq!(s, long);
+
+        let small: Vec<Box<str>> = (0..20).map(|_| Box::from("ab")).collect();
+        let s: InlinableString = small.into_iter().collect();
+        assert!(s.is_heap());
+        assert_eq!(s, "ab".repeat(20));
+
+        let small: Vec<Box<str>> = (0..4).map(|_| Box::from("ab")).collect();
+        let s: InlinableString = small.into_iter().collect();
+        assert!(s.is_inline());
+        assert_eq!(s, "abababab");
+    }
+
+    #[test]
+    fn test_extend_inlinable_string() {
+        let parts: Vec<InlinableString> = vec!["foo".into(), "bar".into(), "baz".into()];
+        let s: InlinableString = parts.iter().collect();
+        assert_eq!(s, "foobarbaz");
+        assert!(s.is_inline());
+
+        let s: InlinableString = parts.into_iter().collect();
+        assert_eq!(s, "foobarbaz");
+        assert!(s.is_inline());
+
+        let parts: Vec<InlinableString> = (0..INLINE_STRING_CAPACITY / 2)
+            .map(|_| InlinableString::from("ab"))
+            .collect();
+        let s: InlinableString = parts.iter().collect();
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let mut s: InlinableString = parts.into_iter().collect();
+        s.extend(vec![InlinableString::from("e")]);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 1);
+        assert!(s.is_heap());
+
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let mut s = InlinableString::from("> ");
+        s.extend(vec![&long]);
+        assert!(s.is_heap());
+        assert_eq!(s, format!("> {}", long));
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut s = InlinableString::from("> ");
+        s.extend_from_slice::<InlinableString>(&[]);
+        assert_eq!(s, "> ");
+        assert!(s.is_inline());
+
+        let parts: Vec<InlinableString> = vec!["foo".into(), "".into(), "bar".into()];
+        s.extend_from_slice(&parts);
+        assert_eq!(s, "> foobar");
+        assert!(s.is_inline());
+
+        // Filling the inline buffer exactly doesn't promote.
+        let parts = vec![InlinableString::from("a"); INLINE_STRING_CAPACITY - s.len()];
+        s.extend_from_slice(&parts);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        // Spilling promotes once, with room for all of the parts.
+        let part = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY));
+        let parts = vec![part; 3];
+        let mut s = InlinableString::from("> ");
+        s.extend_from_slice(&parts);
+        assert!(s.is_heap());
+        assert_eq!(s.len(), 2 + 3 * INLINE_STRING_CAPACITY);
+        assert_eq!(InlinableString::capacity(&s), s.len());
+        assert_eq!(s, format!("> {}", "a".repeat(3 * INLINE_STRING_CAPACITY)));
+
+        // A heap string reserves for the total before copying.
+        let ptr = s.as_ptr();
+        let capacity = InlinableString::capacity(&s);
+        s.truncate(2);
+        s.extend_from_slice(&parts);
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(InlinableString::capacity(&s), capacity);
+
+        let strs = ["é", "t", "é"];
+        let mut s = InlinableString::new();
+        s.extend_from_slice(&strs);
+        assert_eq!(s, "été");
+    }
+
+    #[test]
+    fn test_join_and_concat() {
+        let s = super::join(&["foo", "bar", "baz"], ", ");
+        assert_eq!(s, "foo, bar, baz");
+        assert!(s.is_inline());
+
+        let s = super::concat(&["foo", "bar", "baz"]);
+        assert_eq!(s, "foobarbaz");
+        assert!(s.is_inline());
+
+        let s = super::join(&["foo", "bar"], "");
+        assert_eq!(s, "foobar");
+
+        let empty: [&str; 0] = [];
+        let s = super::join(&empty, "-");
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+        assert_eq!(super::concat(&empty), "");
+
+        let s = super::join(&["only"], "-");
+        assert_eq!(s, "only");
+
+        let parts = vec![InlinableString::from("0123456789"); 10];
+        let s = super::join(&parts, " / ");
+        assert!(s.is_heap());
+        assert_eq!(s, ["0123456789"; 10].join(" / "));
+        assert_eq!(s.capacity(), s.len());
+
+        let parts = vec![String::from("ab"); INLINE_STRING_CAPACITY / 2];
+        let s = super::concat(&parts);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let parts = vec![String::from("ab"); INLINE_STRING_CAPACITY / 2 + 1];
+        let s = super::concat(&parts);
+        assert!(s.is_heap());
+        assert_eq!(s.capacity(), s.len());
+    }
+
+    #[test]
+    fn test_from_char_repeated() {
+        let s = InlinableString::from_char_repeated('x', 0);
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_char_repeated('－', 3);
+        assert_eq!(s, "－－－");
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_char_repeated('-', INLINE_STRING_CAPACITY);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_char_repeated('é', INLINE_STRING_CAPACITY / 2);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_char_repeated('é', INLINE_STRING_CAPACITY / 2 + 1);
+        assert_eq!(s, "é".repeat(INLINE_STRING_CAPACITY / 2 + 1));
+        assert!(s.is_heap());
+        assert_eq!(s.capacity(), s.len());
+
+        let s = InlinableString::from_char_repeated('🦀', 100);
+        assert_eq!(s.len(), 400);
+        assert_eq!(s.chars().count(), 100);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_char_repeated_overflow() {
+        InlinableString::from_char_repeated('é', usize::MAX / 2 + 1);
+    }
+
+    #[test]
+    fn test_replace() {
+        let s = InlinableString::from("hello");
+        let replaced = s.replace("x", "y");
+        assert_eq!(replaced, "hello");
+        assert!(replaced.is_inline());
+
+        let s = InlinableString::from("a,b,c,d");
+        let replaced = s.replace(",", " and then ");
+        assert_eq!(replaced, "a and then b and then c and then d");
+        assert!(replaced.is_heap());
+
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let replaced = long.replace("too long", "short");
+        assert_eq!(replaced, "a string that is short to be stored inline");
+        let replaced = long.replace(" that is too long to be stored inline", "");
+        assert_eq!(replaced, "a string");
+        assert!(replaced.is_inline());
+
+        for &input in &["", "abc", "héllo"] {
+            let s = InlinableString::from(input);
+            assert_eq!(s.replace("", "-"), &input.replace("", "-")[..]);
+            assert_eq!(s.replacen("", "-", 2), &input.replacen("", "-", 2)[..]);
+        }
+    }
+
+    #[test]
+    fn test_replacen() {
+        let s = InlinableString::from("aaaa");
+        assert_eq!(s.replacen("a", "b", 0), "aaaa");
+        assert_eq!(s.replacen("a", "b", 2), "bbaa");
+        assert_eq!(s.replacen("a", "b", 10), "bbbb");
+        assert_eq!(s.replacen("aa", "é", 1), "éaa");
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        let s = InlinableString::from("Hello World");
+        let lower = s.to_lowercase();
+        let upper = s.to_uppercase();
+        assert_eq!(lower, "hello world");
+        assert_eq!(upper, "HELLO WORLD");
+        assert!(lower.is_inline());
+        assert!(upper.is_inline());
+
+        let s = InlinableString::from("ß");
+        assert_eq!(s.to_uppercase(), "SS");
+
+        let s = InlinableString::from("ß".repeat(INLINE_STRING_CAPACITY / 2));
+        assert!(s.is_inline());
+        let upper = s.to_uppercase();
+        assert_eq!(upper, "SS".repeat(INLINE_STRING_CAPACITY / 2));
+        assert!(upper.is_inline());
+
+        let s = InlinableString::from("ßß".repeat(INLINE_STRING_CAPACITY / 4 + 1));
+        let upper = s.to_uppercase();
+        assert!(upper.is_heap());
+
+        for &input in &["İstanbul", "ıiIİ", "ὈΔΥΣΣΕΎΣ", "ΣΑΣ ΣΑΣ", "Σ", "ǅ", "ﬁ"] {
+            let s = InlinableString::from(input);
+            assert_eq!(s.to_lowercase(), &input.to_lowercase()[..]);
+            assert_eq!(s.to_uppercase(), &input.to_uppercase()[..]);
+        }
+    }
+
+    #[test]
+    fn test_from_str_case_conversion() {
+        let input = "ß".repeat(INLINE_STRING_CAPACITY / 2 + 1);
+        let s = InlinableString::from_str_to_uppercase(&input);
+        assert_eq!(s, input.to_uppercase());
+        assert!(s.is_heap());
+
+        let input = "ẞ".repeat(INLINE_STRING_CAPACITY / 3 + 1);
+        assert!(input.len() > INLINE_STRING_CAPACITY);
+        let s = InlinableString::from_str_to_lowercase(&input);
+        assert_eq!(s, input.to_lowercase());
+        assert!(s.len() <= INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        let input = "İ".repeat(INLINE_STRING_CAPACITY / 3);
+        let s = InlinableString::from_str_to_lowercase(&input);
+        assert_eq!(s, input.to_lowercase());
+        assert_eq!(s.len(), input.len() / 2 * 3);
+
+        let input = "ȺȺȺ".repeat(INLINE_STRING_CAPACITY / 9 + 1);
+        assert!(input.len() <= INLINE_STRING_CAPACITY);
+        let s = InlinableString::from_str_to_lowercase(&input);
+        assert_eq!(s, input.to_lowercase());
+        assert!(s.len() > INLINE_STRING_CAPACITY);
+        assert!(s.is_heap());
+
+        for &input in &["Hello", "ὈΔΥΣΣΕΎΣ", "tschüß", "ΣΑΣ ΣΑΣ"] {
+            assert_eq!(InlinableString::from_str_to_lowercase(input), input.to_lowercase());
+            assert_eq!(InlinableString::from_str_to_uppercase(input), input.to_uppercase());
+            assert_eq!(InlinableString::from_str_to_ascii_lowercase(input),
+                       input.to_ascii_lowercase());
+            assert_eq!(InlinableString::from_str_to_ascii_uppercase(input),
+                       input.to_ascii_uppercase());
+        }
+
+        let s = InlinableString::from_str_to_ascii_lowercase("ABC");
+        assert_eq!(s, "abc");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_ascii_case_conversion() {
+        let s = InlinableString::from("é".repeat(INLINE_STRING_CAPACITY / 2 - 1) + "Ab");
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        let lower = s.to_ascii_lowercase();
+        let upper = s.to_ascii_uppercase();
+        assert_eq!(lower, "é".repeat(INLINE_STRING_CAPACITY / 2 - 1) + "ab");
+        assert_eq!(upper, "é".repeat(INLINE_STRING_CAPACITY / 2 - 1) + "AB");
+        assert!(lower.is_inline());
+        assert!(upper.is_inline());
+
+        let s = InlinableString::from("A String That Is Too Long To Be Stored Inline: ÄÖÜ");
+        let lower = s.to_ascii_lowercase();
+        assert_eq!(lower, "a string that is too long to be stored inline: ÄÖÜ");
+        assert!(lower.is_heap());
+        assert_eq!(lower.capacity(), lower.len());
+        assert_eq!(s.to_ascii_uppercase(), "A STRING THAT IS TOO LONG TO BE STORED INLINE: ÄÖÜ");
+    }
+
+    #[test]
+    fn test_make_ascii_case() {
+        let mut s = InlinableString::from("Ǆemal Ünal");
+        assert!(s.is_inline());
+        s.make_ascii_uppercase();
+        assert_eq!(s, "ǄEMAL ÜNAL");
+        s.make_ascii_lowercase();
+        assert_eq!(s, "Ǆemal Ünal".to_ascii_lowercase());
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("Straße, Ærøskøbing, and Some Other Places");
+        assert!(s.is_heap());
+        let ptr = s.as_ptr();
+        s.make_ascii_lowercase();
+        assert_eq!(s, "straße, Ærøskøbing, and some other places");
+        s.make_ascii_uppercase();
+        assert_eq!(s, "STRAßE, ÆRøSKøBING, AND SOME OTHER PLACES");
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_make_case() {
+        let mut s = InlinableString::from("Hello");
+        s.make_uppercase();
+        assert_eq!(s, "HELLO");
+        s.make_lowercase();
+        assert_eq!(s, "hello");
+
+        let mut s = InlinableString::from("Ünïcödé Çàsé Tëxt, Lóng Énôügh");
+        assert!(s.is_heap());
+        let ptr = s.as_ptr();
+        s.make_uppercase();
+        assert_eq!(s, "ÜNÏCÖDÉ ÇÀSÉ TËXT, LÓNG ÉNÔÜGH");
+        s.make_lowercase();
+        assert_eq!(s, "ünïcödé çàsé tëxt, lóng énôügh");
+        assert_eq!(s.as_ptr(), ptr);
+
+        let mut s = InlinableString::from("straße");
+        s.make_uppercase();
+        assert_eq!(s, "STRASSE");
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("ß".repeat(INLINE_STRING_CAPACITY / 2));
+        s.make_uppercase();
+        assert_eq!(s, "SS".repeat(INLINE_STRING_CAPACITY / 2));
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("ȺȺȺ".repeat(INLINE_STRING_CAPACITY / 9 + 1));
+        assert!(s.is_inline());
+        s.make_lowercase();
+        assert_eq!(s, "ⱥⱥⱥ".repeat(INLINE_STRING_CAPACITY / 9 + 1));
+        assert!(s.is_heap());
+
+        for &input in &["İstanbul", "ὈΔΥΣΣΕΎΣ", "ΣΑΣ ΣΑΣ", "ǅ", "ﬁ", "ẞ", "Ǆemal"] {
+            let mut lower = InlinableString::from(input);
+            lower.make_lowercase();
+            assert_eq!(lower, input.to_lowercase());
+            let mut upper = InlinableString::from(input);
+            upper.make_uppercase();
+            assert_eq!(upper, input.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_capitalize() {
+        let s = InlinableString::from("monday");
+        let capitalized = s.capitalize();
+        assert_eq!(capitalized, "Monday");
+        assert!(capitalized.is_inline());
+
+        assert_eq!(InlinableString::new().capitalize(), "");
+        assert_eq!(InlinableString::from("ébène").capitalize(), "Ébène");
+        assert_eq!(InlinableString::from("ßen").capitalize(), "SSen");
+        assert_eq!(InlinableString::from("ǆungla").capitalize(), "Ǆungla");
+        assert_eq!(InlinableString::from("1st").capitalize(), "1st");
+
+        let s = InlinableString::from("ß".to_string() + &"a".repeat(INLINE_STRING_CAPACITY - 2));
+        assert!(s.is_inline());
+        let capitalized = s.capitalize();
+        assert_eq!(capitalized.len(), INLINE_STRING_CAPACITY);
+        assert!(capitalized.is_inline());
+
+        let s = InlinableString::from("ß".to_string() + &"a".repeat(INLINE_STRING_CAPACITY - 1));
+        let capitalized = s.capitalize();
+        assert_eq!(capitalized, "SS".to_string() + &"a".repeat(INLINE_STRING_CAPACITY - 1));
+        assert!(capitalized.is_heap());
+        assert_eq!(capitalized.capacity(), capitalized.len());
+    }
+
+    #[test]
+    fn test_capitalize_in_place() {
+        let mut s = InlinableString::new();
+        s.capitalize_in_place();
+        assert_eq!(s, "");
+
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = s.as_ptr();
+        s.capitalize_in_place();
+        assert_eq!(s, "A string that is too long to be stored inline");
+        assert_eq!(s.as_ptr(), ptr);
+
+        let mut s = InlinableString::from("ßen");
+        s.capitalize_in_place();
+        assert_eq!(s, "SSen");
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("ȿ");
+        s.capitalize_in_place();
+        assert_eq!(s, "Ȿ");
+    }
+
+    #[test]
+    fn test_reverse_in_place() {
+        let mut s = InlinableString::new();
+        s.reverse_in_place();
+        assert_eq!(s, "");
+
+        let mut s = InlinableString::from("hello");
+        s.reverse_in_place();
+        assert_eq!(s, "olleh");
+
+        for &input in &["aé𝄞β€z", "𝄞", "e\u{301}x", "日本語のテキストと少し長めの文字列"] {
+            let mut s = InlinableString::from(input);
+            s.reverse_in_place();
+            assert_eq!(s, input.chars().rev().collect::<String>());
+            s.reverse_in_place();
+            assert_eq!(s, input);
+        }
+
+        let input = "é".repeat(INLINE_STRING_CAPACITY / 2 - 2) + "abcd";
+        let mut s = InlinableString::from(&input[..]);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        s.reverse_in_place();
+        assert_eq!(s, input.chars().rev().collect::<String>());
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = s.as_ptr();
+        s.reverse_in_place();
+        assert_eq!(s, "enilni derots eb ot gnol oot si taht gnirts a");
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_trim_matches_in_place() {
+        let mut s = InlinableString::from("＂＂quoted＂");
+        s.trim_matches_in_place('＂');
+        assert_eq!(s, "quoted");
+
+        let mut s = InlinableString::from("＂quoted＂");
+        s.trim_start_matches_in_place('＂');
+        assert_eq!(s, "quoted＂");
+        s.trim_end_matches_in_place('＂');
+        assert_eq!(s, "quoted");
+
+        let mut s = InlinableString::from("////");
+        s.trim_matches_in_place('/');
+        assert_eq!(s, "");
+
+        let mut s = InlinableString::from("é-é");
+        s.trim_matches_in_place('/');
+        assert_eq!(s, "é-é");
+        s.trim_start_matches_in_place('-');
+        s.trim_end_matches_in_place('-');
+        assert_eq!(s, "é-é");
+
+        let mut s = InlinableString::from("/// a string that is too long to be stored inline ///");
+        let ptr = s.as_ptr();
+        s.trim_matches_in_place_with(|c| c == '/' || c == ' ');
+        assert_eq!(s, "a string that is too long to be stored inline");
+        assert_eq!(s.as_ptr(), ptr);
+        assert!(s.is_heap());
+
+        let mut s = InlinableString::from("123abc456");
+        s.trim_matches_in_place_with(|c| c.is_ascii_digit());
+        assert_eq!(s, "abc");
+        s.trim_matches_in_place_with(|_| true);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_strip_affix_in_place() {
+        let mut s = InlinableString::from("«héllo»");
+        assert!(s.strip_prefix_in_place("«"));
+        assert!(s.strip_suffix_in_place("»"));
+        assert_eq!(s, "héllo");
+        assert!(s.is_inline());
+
+        assert!(!s.strip_prefix_in_place("llo"));
+        assert!(!s.strip_suffix_in_place("hé"));
+        assert!(!s.strip_prefix_in_place("héllo!"));
+        assert_eq!(s, "héllo");
+
+        assert!(s.strip_prefix_in_place(""));
+        assert!(s.strip_suffix_in_place(""));
+        assert_eq!(s, "héllo");
+
+        assert!(s.strip_prefix_in_place("héllo"));
+        assert_eq!(s, "");
+        let mut s = InlinableString::from("héllo");
+        assert!(s.strip_suffix_in_place("héllo"));
+        assert_eq!(s, "");
+
+        let mut s = InlinableString::from("prefix: a string that is too long to be stored inline");
+        let ptr = s.as_ptr();
+        assert!(s.strip_prefix_in_place("prefix: "));
+        assert!(s.strip_suffix_in_place(" inline"));
+        assert_eq!(s, "a string that is too long to be stored");
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_replace_in_place() {
+        let mut s = InlinableString::from("a\\b\\c");
+        s.replace_in_place("\\", "/");
+        assert_eq!(s, "a/b/c");
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("one\r\ntwo\r\nthree\r\nfour\r\nfive\r\nsix");
+        assert!(s.is_heap());
+        let ptr = s.as_ptr();
+        s.replace_in_place("\r\n", "\n");
+        assert_eq!(s, "one\ntwo\nthree\nfour\nfive\nsix");
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+
+        let mut s = InlinableString::from("héllo wörld");
+        s.replace_in_place("ö", "o");
+        s.replace_in_place("é", "e");
+        assert_eq!(s, "hello world");
+
+        let mut s = InlinableString::from("a-b-c");
+        s.replace_in_place("-", "+");
+        assert_eq!(s, "a+b+c");
+        s.replace_in_place("", "");
+        assert_eq!(s, "a+b+c");
+    }
+
+    #[test]
+    fn test_replace_in_place_adjacent_matches() {
+        let mut s = InlinableString::from("xxxxxab");
+        s.replace_in_place("xx", "y");
+        assert_eq!(s, "yyxab");
+
+        let mut s = InlinableString::from("ééé");
+        s.replace_in_place("é", "");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_replace_in_place_growing() {
+        let mut s = InlinableString::from("a,b,c,d");
+        s.replace_in_place(",", " and then ");
+        assert_eq!(s, "a and then b and then c and then d");
+        assert!(s.is_heap());
+
+        let mut s = InlinableString::from("abc");
+        s.replace_in_place("", "-");
+        assert_eq!(s, "-a-b-c-");
+    }
+
+    #[test]
+    fn test_assign() {
+        let long = "a string that is too long to be stored inline";
+
+        let mut s = InlinableString::with_capacity(100);
+        let ptr = s.as_ptr();
+        s.assign("short");
+        assert_eq!(s, "short");
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        s.assign(long);
+        assert_eq!(s, long);
+        assert_eq!(s.as_ptr(), ptr);
+
+        let mut s = InlinableString::from("inline");
+        s.assign("still inline");
+        assert_eq!(s, "still inline");
+        assert!(s.is_inline());
+        s.assign(long);
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+
+        let longer = long.repeat(2);
+        s.assign(&longer);
+        assert_eq!(s, &longer[..]);
+        assert!(s.is_heap());
+
+        let mut s = InlinableString::from_string_buffer(String::from("x"));
+        s.assign("too big");
+        assert_eq!(s, "too big");
+        assert!(s.is_inline());
+
+        s.assign("");
+        assert_eq!(s, "");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut s = InlinableString::from("ab");
+        s.resize(INLINE_STRING_CAPACITY, '-');
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+
+        s.resize(INLINE_STRING_CAPACITY + 2, 'é');
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 2);
+        assert!(s.ends_with("-é"));
+        assert!(s.is_heap());
+
+        s.resize(INLINE_STRING_CAPACITY, 'x');
+        assert!(s.ends_with("--"));
+        s.resize(2, 'x');
+        assert_eq!(s, "ab");
+
+        let mut s = InlinableString::from("hé!");
+        s.resize(3, 'x');
+        assert_eq!(s, "hé");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_resize_inside_char() {
+        let mut s = InlinableString::from("hé");
+        s.resize(2, 'x');
+    }
+
+    #[test]
+    fn test_add() {
+        let s = InlinableString::from("foo") + "bar";
+        assert_eq!(s, "foobar");
+        assert!(s.is_inline());
+
+        let long_str = "a string that is too long to be stored inline";
+        let s = InlinableString::from("foo") + long_str;
+        assert_eq!(s, format!("foo{}", long_str));
+        assert!(s.is_heap());
+    }
+
+    #[test]
+    fn test_add_inlinable_string() {
+        let a = InlinableString::from("foo");
+        let b = InlinableString::from("bar");
+        assert_eq!(a.clone() + b.clone(), "foobar");
+        assert_eq!(a.clone() + &b, "foobar");
+        assert_eq!(a.clone() + &InlineString::from("baz"), "foobaz");
+        assert!((a.clone() + &b).is_inline());
+
+        let long_str = "a string that is too long to be stored inline";
+        let long = InlinableString::from(long_str);
+        assert_eq!(a.clone() + long.clone(), format!("foo{}", long_str));
+        assert_eq!(long.clone() + &a, format!("{}foo", long_str));
+        assert_eq!(long.clone() + &InlineString::from("baz"), format!("{}baz", long_str));
+
+        // Two 20-byte inline strings spill into a single exact allocation.
+        let twenty = InlinableString::from("x".repeat(20));
+        let s = twenty.clone() + &twenty;
+        assert!(s.is_heap());
+        assert_eq!(s.len(), 40);
+        assert_eq!(InlinableString::capacity(&s), 40);
+        let s = twenty.clone() + twenty.clone();
+        assert_eq!(InlinableString::capacity(&s), 40);
+        let s = twenty.clone() + &InlineString::from(&"x".repeat(20)[..]);
+        assert_eq!(InlinableString::capacity(&s), 40);
+        assert_eq!(s, "x".repeat(40));
+    }
+
+    #[test]
+    fn test_add_assign() {
+        let mut s = InlinableString::from("foo");
+        s += "bar";
+        s += &String::from("baz");
+        assert_eq!(s, "foobarbaz");
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 1));
+        s += "b";
+        assert!(s.is_inline());
+        s += "c";
+        assert!(s.is_heap());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 1);
+        assert!(s.ends_with("bc"));
+
+        let long_str = String::from("a string that is too long to be stored inline");
+        let mut s = InlinableString::new();
+        s += &long_str;
+        s += "!";
+        assert_eq!(s, format!("{}!", long_str));
+    }
+
+    #[test]
+    fn test_add_char() {
+        let s = InlinableString::from("foo") + '/';
+        assert_eq!(s, "foo/");
+        let mut s = s + '𝄞';
+        assert_eq!(s, "foo/𝄞");
+        s += '.';
+        s += 'é';
+        assert_eq!(s, "foo/𝄞.é");
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 1));
+        s += 'b';
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+
+        let s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 1)) + '𝄞';
+        assert!(s.is_heap());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 3);
+        assert!(s.ends_with('𝄞'));
+    }
+
+    #[test]
+    fn test_deref_mut() {
+        let mut s = InlinableString::from("héllo");
+        s.make_ascii_uppercase();
+        assert_eq!(s, "HéLLO");
+        s[..1].make_ascii_lowercase();
+        assert_eq!(s, "héLLO");
+        assert_eq!(s.len(), 6);
+        assert!(s.is_inline());
+
+        let long_str = "a string that is too long to be stored inline, née heap";
+        let mut s = InlinableString::from(long_str);
+        s.make_ascii_uppercase();
+        assert_eq!(s, long_str.to_ascii_uppercase());
+        assert_eq!(s.len(), long_str.len());
+        assert!(s.is_heap());
+        assert!(::std::str::from_utf8(s.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_as_mut_and_borrow_mut() {
+        use std::borrow::BorrowMut;
+
+        fn normalize<T: BorrowMut<str>>(t: &mut T) {
+            t.borrow_mut().make_ascii_lowercase();
+        }
+
+        fn shout<T: AsMut<str>>(t: &mut T) {
+            t.as_mut().make_ascii_uppercase();
+        }
+
+        let mut s = InlinableString::from("HeLLo");
+        normalize(&mut s);
+        assert_eq!(s, "hello");
+        shout(&mut s);
+        assert_eq!(s, "HELLO");
+        assert!(s.is_inline());
+
+        let long_str = "A String That Is Too Long To Be Stored Inline";
+        let mut s = InlinableString::from(long_str);
+        normalize(&mut s);
+        assert_eq!(s, long_str.to_ascii_lowercase());
+        shout(&mut s);
+        assert_eq!(s, long_str.to_ascii_uppercase());
+        assert!(s.is_heap());
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut s = InlinableString::from("abcdefg");
+        s[2..5].make_ascii_uppercase();
+        assert_eq!(s, "abCDEfg");
+        s[..1].make_ascii_uppercase();
+        s[6..].make_ascii_uppercase();
+        assert_eq!(s, "AbCDEfG");
+        s[..].make_ascii_lowercase();
+        assert_eq!(s, "abcdefg");
+
+        let long_str = "a string that is too long to be stored inline";
+        let mut s = InlinableString::from(long_str);
+        s[2..8].make_ascii_uppercase();
+        s[..1].make_ascii_uppercase();
+        s[long_str.len() - 6..].make_ascii_uppercase();
+        assert_eq!(s, "A STRING that is too long to be stored INLINE");
+        s[..].make_ascii_lowercase();
+        assert_eq!(s, long_str);
+    }
+
+    #[test]
+    fn test_index_inclusive() {
+        let long_str = "a string that is too long to be stored inline, née heap";
+        for s in &[InlinableString::from("héllo"), InlinableString::from(long_str)] {
+            let last = s.len() - 1;
+            assert_eq!(&s[..=last], &s[..]);
+            assert_eq!(&s[0..=last], &s[..]);
+            assert_eq!(&s[1..=last], &s[1..]);
+            assert_eq!(&s[..=0], &s[..1]);
+        }
+
+        // An inclusive range that ends on the last byte of a multi-byte char.
+        let s = InlinableString::from("héllo");
+        assert_eq!(&s[1..=2], "é");
+        assert_eq!(&s[..=2], "hé");
+
+        let mut s = InlinableString::from("héllo");
+        s[3..=4].make_ascii_uppercase();
+        s[..=0].make_ascii_uppercase();
+        assert_eq!(s, "HéLLo");
+
+        let mut s = InlinableString::from(long_str);
+        s[2..=7].make_ascii_uppercase();
+        s[..=0].make_ascii_uppercase();
+        assert_eq!(&s[..8], "A STRING");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_inclusive_overflow_inline() {
+        let s = InlinableString::from("hello");
+        let _ = &s[..=usize::MAX];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_inclusive_overflow_heap() {
+        let s = InlinableString::from("a string that is too long to be stored inline");
+        let _ = &s[..=usize::MAX];
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a char boundary")]
+    fn test_index_inclusive_char_boundary() {
+        let s = InlinableString::from("héllo");
+        let _ = &s[..=1];
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a char boundary")]
+    fn test_index_mut_char_boundary_inline() {
+        let mut s = InlinableString::from("héllo");
+        s[..2].make_ascii_uppercase();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a char boundary")]
+    fn test_index_mut_char_boundary_heap() {
+        let mut s = InlinableString::from("héllo, this string is too long to be stored inline");
+        s[2..].make_ascii_uppercase();
+    }
+
+    // Next, some general sanity tests.
+
+    #[test]
+    fn test_new() {
+        let s = <InlinableString as StringExt>::new();
+        assert!(StringExt::is_empty(&s));
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let s = <InlinableString as StringExt>::with_capacity(10);
+        assert!(StringExt::capacity(&s) >= 10);
+    }
+
+    #[test]
+    fn test_try_with_capacity() {
+        let s = <InlinableString as StringExt>::try_with_capacity(10).unwrap();
+        assert_eq!(StringExt::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let s = <InlinableString as StringExt>::try_with_capacity(100).unwrap();
+        assert!(StringExt::capacity(&s) >= 100);
+
+        assert!(<InlinableString as StringExt>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_from_utf8() {
+        let s = <InlinableString as StringExt>::from_utf8(vec![104, 101, 108, 108, 111]);
+        let s = s.unwrap();
+        assert_eq!(s, "hello");
+        assert!(s.is_inline());
+
+        let long = "a string that is too long to be stored inline";
+        let mut v = Vec::with_capacity(100);
+        v.extend_from_slice(long.as_bytes());
+        let ptr = v.as_ptr();
+        let s = <InlinableString as StringExt>::from_utf8(v).unwrap();
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s.capacity(), 100);
+
+        let invalid = vec![b'a', 0xFF, b'b'];
+        let err = <InlinableString as StringExt>::from_utf8(invalid.clone()).unwrap_err();
+        assert_eq!(err.utf8_error().valid_up_to(), 1);
+        assert_eq!(err.into_bytes(), invalid);
+    }
+
+    #[test]
+    fn test_from_utf8_unchecked() {
+        let s = unsafe { InlinableString::from_utf8_unchecked(b"hello".to_vec()) };
+        assert_eq!(s, "hello");
+        assert!(s.is_inline());
+
+        let long = "a string that is too long to be stored inline";
+        let mut v = Vec::with_capacity(100);
+        v.extend_from_slice(long.as_bytes());
+        let ptr = v.as_ptr();
+        let s = unsafe { InlinableString::from_utf8_unchecked(v) };
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s.capacity(), 100);
+    }
+
+    #[test]
+    fn test_from_utf16() {
+        let v = &mut [0xD834, 0xDD1E, 0x006d, 0x0075,
+                      0x0073, 0x0069, 0x0063];
+        let s = <InlinableString as StringExt>::from_utf16(v);
+        let s = s.unwrap();
+        assert_eq!(s, "𝄞music");
+        assert!(s.is_inline());
+
+        // Unpaired surrogates.
+        assert!(<InlinableString as StringExt>::from_utf16(&v[..1]).is_err());
+        assert!(<InlinableString as StringExt>::from_utf16(&v[1..]).is_err());
+
+        // 𝄞 followed by enough ASCII to land right on, and just past, the
+        // inline capacity.
+        let mut v = vec![0xD834, 0xDD1E];
+        v.extend(vec![0x61; INLINE_STRING_CAPACITY - 4]);
+        let s = <InlinableString as StringExt>::from_utf16(&v).unwrap();
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY);
+        assert!(s.is_inline());
+        v.push(0x61);
+        let s = <InlinableString as StringExt>::from_utf16(&v).unwrap();
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 1);
+        assert!(s.is_heap());
+    }
+
+    #[test]
+    fn test_from_utf16_lossy() {
+        let input = b"Hello \xF0\x90\x80World";
+        let output = <InlinableString as StringExt>::from_utf8_lossy(input);
+        assert_eq!(output, "Hello \u{FFFD}World");
+
+        // 𝄞mu<lone surrogate>ic
+        let v = &[0xD834, 0xDD1E, 0x006d, 0x0075, 0xDD1E, 0x0069, 0x0063];
+        let s = <InlinableString as StringExt>::from_utf16_lossy(v);
+        assert_eq!(s, "𝄞mu\u{FFFD}ic");
+        assert!(s.is_inline());
+
+        // Each lone surrogate becomes three bytes of U+FFFD.
+        let v = vec![0xD834; INLINE_STRING_CAPACITY / 3];
+        let s = <InlinableString as StringExt>::from_utf16_lossy(&v);
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY / 3 * 3);
+        assert!(s.is_inline());
+        let v = vec![0xD834; INLINE_STRING_CAPACITY / 3 + 1];
+        let s = <InlinableString as StringExt>::from_utf16_lossy(&v);
+        assert!(s.is_heap());
+    }
+
+    #[test]
+    fn test_from_utf16le() {
+        // 𝄞music
+        let v = &[0x34, 0xD8, 0x1E, 0xDD, 0x6d, 0x00, 0x75, 0x00,
+                  0x73, 0x00, 0x69, 0x00, 0x63, 0x00];
+        let s = <InlinableString as StringExt>::from_utf16le(v).unwrap();
+        assert_eq!(s, "𝄞music");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        // Odd trailing byte.
+        assert!(<InlinableString as StringExt>::from_utf16le(&v[..13]).is_err());
+        // Unpaired surrogate.
+        assert!(<InlinableString as StringExt>::from_utf16le(&v[..2]).is_err());
+
+        let long: Vec<u8> = (0..INLINE_STRING_CAPACITY + 1).flat_map(|_| vec![0x61, 0x00]).collect();
+        let s = <InlinableString as StringExt>::from_utf16le(&long).unwrap();
+        assert_eq!(s, String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a')));
+    }
+
+    #[test]
+    fn test_from_utf16le_lossy() {
+        // 𝄞mu<invalid>ic<odd byte>
+        let v = &[0x34, 0xD8, 0x1E, 0xDD, 0x6d, 0x00, 0x75, 0x00,
+                  0x1E, 0xDD, 0x69, 0x00, 0x63, 0x00, 0x34];
+        let s = <InlinableString as StringExt>::from_utf16le_lossy(v);
+        assert_eq!(s, "𝄞mu\u{FFFD}ic\u{FFFD}");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    fn test_from_utf16be() {
+        // 𝄞music
+        let v = &[0xD8, 0x34, 0xDD, 0x1E, 0x00, 0x6d, 0x00, 0x75,
+                  0x00, 0x73, 0x00, 0x69, 0x00, 0x63];
+        let s = <InlinableString as StringExt>::from_utf16be(v).unwrap();
+        assert_eq!(s, "𝄞music");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        // Odd trailing byte.
+        assert!(<InlinableString as StringExt>::from_utf16be(&v[..13]).is_err());
+        // Unpaired surrogate.
+        assert!(<InlinableString as StringExt>::from_utf16be(&v[..2]).is_err());
+        assert!(<InlinableString as StringExt>::from_utf16be(&v[2..]).is_err());
+    }
+
+    #[test]
+    fn test_from_utf16be_lossy() {
+        // <invalid>mu𝄞ic<odd byte>
+        let v = &[0xDD, 0x1E, 0x00, 0x6d, 0x00, 0x75, 0xD8, 0x34,
+                  0xDD, 0x1E, 0x00, 0x69, 0x00, 0x63, 0x00];
+        let s = <InlinableString as StringExt>::from_utf16be_lossy(v);
+        assert_eq!(s, "\u{FFFD}mu𝄞ic\u{FFFD}");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    fn test_from_utf8_lossy_owned() {
+        let s = InlinableString::from_utf8_lossy_owned(b"small".to_vec());
+        assert_eq!(s, "small");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let long = "a string that is too long to be stored inline";
+        let mut v = Vec::with_capacity(100);
+        v.extend_from_slice(long.as_bytes());
+        let ptr = v.as_ptr();
+        let s = InlinableString::from_utf8_lossy_owned(v);
+        assert_eq!(s, long);
+        assert_eq!(InlinableString::capacity(&s), 100);
+        assert_eq!(s.as_ptr(), ptr);
+
+        let s = InlinableString::from_utf8_lossy_owned(b"Hello \xF0\x90\x80World".to_vec());
+        assert_eq!(s, "Hello \u{FFFD}World");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let s = InlinableString::from_utf8_lossy_owned(vec![0xFF; 40]);
+        assert_eq!(s, "\u{FFFD}".repeat(40));
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut s = InlinableString::from("aaaa");
+        s.remove_matches("aa");
+        assert_eq!(s, "");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let mut s = InlinableString::from("aaa");
+        s.remove_matches("aa");
+        assert_eq!(s, "a");
+
+        let mut s = InlinableString::from("ßaßbßß");
+        s.remove_matches("ß");
+        assert_eq!(s, "ab");
+
+        let mut s = InlinableString::from("αβγ");
+        s.remove_matches("βγ");
+        assert_eq!(s, "α");
+
+        let mut s = InlinableString::from("hello");
+        s.remove_matches("xyz");
+        assert_eq!(s, "hello");
+        s.remove_matches("");
+        assert_eq!(s, "hello");
+
+        let long = "a string that is too long, too long to be stored inline";
+        let mut s = InlinableString::from(long);
+        let ptr = s.as_ptr();
+        let cap = InlinableString::capacity(&s);
+        s.remove_matches("too long");
+        assert_eq!(s, "a string that is ,  to be stored inline");
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(InlinableString::capacity(&s), cap);
+    }
+
+    #[test]
+    fn test_into_chars() {
+        let s = InlinableString::from("α𝄞bé");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        let mut chars = s.into_chars();
+
+        // The remaining characters are read straight out of the iterator's
+        // own inline buffer, not from the heap.
+        let start = &chars as *const _ as usize;
+        let end = start + mem::size_of_val(&chars);
+        let ptr = chars.as_str().as_ptr() as usize;
+        assert!(start <= ptr && ptr < end);
+
+        assert_eq!(chars.size_hint(), (3, Some(9)));
+        assert_eq!(chars.next(), Some('α'));
+        assert_eq!(chars.next_back(), Some('é'));
+        assert_eq!(chars.as_str(), "𝄞b");
+        assert_eq!(chars.next_back(), Some('b'));
+        assert_eq!(chars.next(), Some('𝄞'));
+        assert_eq!(chars.as_str(), "");
+        assert_eq!(chars.next(), None);
+        assert_eq!(chars.next_back(), None);
+
+        let long = "a string that is too long to be stored inline: αβγ";
+        let chars = InlinableString::from(long).into_chars();
+        assert_eq!(chars.rev().collect::<String>(), long.chars().rev().collect::<String>());
+    }
+
+    #[test]
+    fn test_push_utf8_lossy() {
+        let mut s = InlinableString::from("a");
+        s.push_utf8_lossy(b"b\xFFc\xC0\x80d");
+        assert_eq!(s, "ab\u{FFFD}c\u{FFFD}\u{FFFD}d");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        // Truncated multi-byte sequence at the end of the input.
+        s.push_utf8_lossy(b"\xF0\x9D\x84");
+        assert_eq!(s, "ab\u{FFFD}c\u{FFFD}\u{FFFD}d\u{FFFD}");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        s.push_utf8_lossy(b"");
+        assert_eq!(s, "ab\u{FFFD}c\u{FFFD}\u{FFFD}d\u{FFFD}");
+
+        // Crosses the inline capacity.
+        let mut s = InlinableString::from("x".repeat(INLINE_STRING_CAPACITY - 2).as_str());
+        s.push_utf8_lossy(b"y\xFF");
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 2);
+        assert!(s.ends_with("xy\u{FFFD}"));
+        assert!(InlinableString::capacity(&s) > INLINE_STRING_CAPACITY);
+    }
+
+    #[test]
+    fn test_into_bytes() {
+        let s = InlinableString::from("hello");
+        let bytes = StringExt::into_bytes(s);
+        assert_eq!(bytes, [104, 101, 108, 108, 111]);
+    }
+
+    #[test]
+    fn test_into_boxed_str() {
+        let empty = InlinableString::new();
+        assert_eq!(&*StringExt::into_boxed_str(empty), "");
+
+        let short = InlinableString::from("hello");
+        assert_eq!(&*StringExt::into_boxed_str(short), "hello");
+
+        let long = InlinableString::from("hello world hello world hello world");
+        assert_eq!(&*StringExt::into_boxed_str(long), "hello world hello world hello world");
+    }
+
+    #[test]
+    fn test_leak() {
+        let short: &'static mut str = {
+            let s = InlinableString::from("hello");
+            StringExt::leak(s)
+        };
+        let long: &'static mut str = {
+            let s = InlinableString::from("hello world hello world hello world");
+            StringExt::leak(s)
+        };
+        assert_eq!(short, "hello");
+        assert_eq!(long, "hello world hello world hello world");
+    }
+
+    #[test]
+    fn test_capacity() {
+        let s = <InlinableString as StringExt>::with_capacity(100);
+        assert!(InlinableString::capacity(&s) >= 100);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut s = <InlinableString as StringExt>::new();
+        StringExt::reserve(&mut s, 100);
+        assert!(InlinableString::capacity(&s) >= 100);
+    }
+
+    #[test]
+    fn test_reserve_exact() {
+        let mut s = <InlinableString as StringExt>::new();
+        StringExt::reserve_exact(&mut s, 100);
+        assert!(InlinableString::capacity(&s) >= 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_overflow_empty_inline() {
+        let mut s = InlinableString::new();
+        StringExt::reserve(&mut s, usize::MAX);
+    }
 
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_overflow_inline() {
+        let mut s = InlinableString::from("foo");
+        StringExt::reserve(&mut s, usize::MAX);
     }
 
-    #[inline]
-    unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
-        match *self {
-            InlinableString::Heap(ref mut s) => &mut s.as_mut_vec()[..],
-            InlinableString::Inline(ref mut s) => s.as_mut_slice(),
-        }
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_overflow_heap() {
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        StringExt::reserve(&mut s, usize::MAX);
     }
 
-    #[inline]
-    fn len(&self) -> usize {
-        match *self {
-            InlinableString::Heap(ref s) => s.len(),
-            InlinableString::Inline(ref s) => s.len(),
-        }
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_exact_overflow_empty_inline() {
+        let mut s = InlinableString::new();
+        StringExt::reserve_exact(&mut s, usize::MAX);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{InlinableString, StringExt, INLINE_STRING_CAPACITY};
-    use std::cmp::Ordering;
-    use std::iter::FromIterator;
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_exact_overflow_inline() {
+        let mut s = InlinableString::from("foo");
+        StringExt::reserve_exact(&mut s, usize::MAX);
+    }
 
     #[test]
-    fn test_size() {
-        use std::mem::size_of;
-        assert_eq!(size_of::<InlinableString>(), 4 * size_of::<usize>());
+    #[should_panic(expected = "capacity overflow")]
+    fn test_reserve_exact_overflow_heap() {
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        StringExt::reserve_exact(&mut s, usize::MAX);
     }
 
-    // First, specifically test operations that overflow InlineString's capacity
-    // and require promoting the string to heap allocation.
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_with_capacity_overflow() {
+        let _ = <InlinableString as StringExt>::with_capacity(usize::MAX);
+    }
 
     #[test]
-    fn test_push_str() {
-        let mut s = InlinableString::new();
+    fn test_with_capacity_lazy() {
+        let mut s = InlinableString::with_capacity_lazy(100);
+        assert!(s.is_inline());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
         s.push_str("small");
-        assert_eq!(s, "small");
+        assert!(s.is_inline());
 
-        let long_str = "this is a really long string that is much larger than
-                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
-        s.push_str(long_str);
-        assert_eq!(s, String::from("small") + long_str);
+        // Promotion sizes the buffer from the contents, not the forgotten hint.
+        let long = "a".repeat(INLINE_STRING_CAPACITY + 1);
+        s.push_str(&long);
+        assert!(s.is_heap());
+        assert_eq!(InlinableString::capacity(&s), 2 * INLINE_STRING_CAPACITY);
     }
 
     #[test]
-    fn test_write() {
-        use fmt::Write;
-        let mut s = InlinableString::new();
-        write!(&mut s, "small").expect("!write");
-        assert_eq!(s, "small");
+    fn test_reserve_lazy() {
+        let mut s = InlinableString::from("foo");
+        s.reserve_lazy(100);
+        assert!(s.is_inline());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        s.push_str("bar");
+        assert_eq!(s, "foobar");
+        assert!(s.is_inline());
 
-        let long_str = "this is a really long string that is much larger than
-                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
-        write!(&mut s, "{}", long_str).expect("!write");
-        assert_eq!(s, String::from("small") + long_str);
+        // The deferred allocation happens once an append overflows.
+        let long = "a string that is too long to be stored inline";
+        s.push_str(long);
+        assert!(s.is_heap());
+        assert_eq!(s, format!("foobar{}", long));
+
+        s.reserve_lazy(100);
+        assert!(InlinableString::capacity(&s) >= s.len() + 100);
+
+        // `reserve_exact` on a lazily reserved string still allocates eagerly.
+        let mut s = InlinableString::from("foo");
+        s.reserve_lazy(100);
+        StringExt::reserve_exact(&mut s, 100);
+        assert!(s.is_heap());
+        assert_eq!(InlinableString::capacity(&s), 103);
+
+        // A lazy hint that fits inline changes nothing for `reserve_exact`.
+        let mut s = InlinableString::from("foo");
+        s.reserve_lazy(10);
+        StringExt::reserve_exact(&mut s, 10);
+        assert!(s.is_inline());
     }
 
     #[test]
-    fn test_push() {
-        let mut s = InlinableString::new();
+    fn test_reserve_promotion_growth() {
+        let mut s = InlinableString::from("foo");
+        StringExt::reserve(&mut s, INLINE_STRING_CAPACITY);
+        assert!(s.is_heap());
+        assert!(InlinableString::capacity(&s) > INLINE_STRING_CAPACITY + 3);
 
-        for _ in 0..INLINE_STRING_CAPACITY {
-            s.push('a');
+        let mut s = InlinableString::from("foo");
+        StringExt::reserve_exact(&mut s, INLINE_STRING_CAPACITY);
+        assert!(s.is_heap());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY + 3);
+
+        let mut s = InlinableString::from("foo");
+        assert!(StringExt::try_reserve(&mut s, INLINE_STRING_CAPACITY).is_ok());
+        assert!(InlinableString::capacity(&s) > INLINE_STRING_CAPACITY + 3);
+
+        let mut s = InlinableString::from("foo");
+        assert!(StringExt::try_reserve_exact(&mut s, INLINE_STRING_CAPACITY).is_ok());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY + 3);
+
+        // `reserve(1); push(c)` across the boundary doesn't reallocate on
+        // every push.
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY));
+        StringExt::reserve(&mut s, 1);
+        let ptr = s.as_ptr();
+        for _ in 0..INLINE_STRING_CAPACITY / 2 {
+            StringExt::reserve(&mut s, 1);
+            s.push('b');
         }
-        s.push('a');
+        assert_eq!(s.as_ptr(), ptr);
+    }
 
-        assert_eq!(s, String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a')));
+    #[test]
+    fn test_try_reserve() {
+        let mut s = InlinableString::from("foo");
+        assert!(StringExt::try_reserve(&mut s, 10).is_ok());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        assert!(StringExt::try_reserve(&mut s, usize::MAX).is_err());
+        assert_eq!(s, "foo");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        assert!(StringExt::try_reserve(&mut s, 100).is_ok());
+        assert_eq!(s, "foo");
+        assert!(InlinableString::capacity(&s) >= 103);
+
+        assert!(StringExt::try_reserve(&mut s, usize::MAX).is_err());
+        assert_eq!(s, "foo");
     }
 
     #[test]
-    fn test_insert() {
-        let mut s = InlinableString::new();
+    fn test_try_reserve_exact() {
+        let mut s = InlinableString::from("foo");
+        assert!(StringExt::try_reserve_exact(&mut s, 10).is_ok());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        assert!(StringExt::try_reserve_exact(&mut s, usize::MAX).is_err());
+        assert_eq!(s, "foo");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        assert!(StringExt::try_reserve_exact(&mut s, 100).is_ok());
+        assert_eq!(s, "foo");
+        assert!(InlinableString::capacity(&s) >= 103);
+    }
 
+    #[test]
+    fn test_try_push() {
+        let mut s = InlinableString::new();
         for _ in 0..INLINE_STRING_CAPACITY {
-            s.insert(0, 'a');
+            assert!(s.try_push('a').is_ok());
         }
-        s.insert(0, 'a');
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
 
-        assert_eq!(s, String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a')));
+        assert!(s.try_push('é').is_ok());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY + 2);
+        assert!(s.ends_with("aé"));
     }
 
-    // Next, some general sanity tests.
+    #[test]
+    fn test_try_push_str() {
+        let mut s = InlinableString::from("foo");
+        assert!(s.try_push_str("bar").is_ok());
+        assert_eq!(s, "foobar");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let long = "a string that is too long to be stored inline";
+        assert!(s.try_push_str(long).is_ok());
+        assert_eq!(s, format!("foobar{}", long));
+    }
 
     #[test]
-    fn test_new() {
-        let s = <InlinableString as StringExt>::new();
-        assert!(StringExt::is_empty(&s));
+    fn test_clone() {
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        s.truncate(8);
+        assert!(s.is_heap());
+        let cloned = s.clone();
+        assert!(cloned.is_inline());
+        assert!(s.is_heap());
+        assert_eq!(cloned, s);
+        assert_eq!(cloned, "a string");
+
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let cloned = long.clone();
+        assert!(cloned.is_heap());
+        assert_eq!(cloned, long);
+
+        let small = InlinableString::from("small");
+        let cloned = small.clone();
+        assert!(cloned.is_inline());
+        assert_eq!(cloned, small);
     }
 
     #[test]
-    fn test_with_capacity() {
-        let s = <InlinableString as StringExt>::with_capacity(10);
-        assert!(StringExt::capacity(&s) >= 10);
+    fn test_clone_from() {
+        let long = "a string that is too long to be stored inline";
+
+        let mut dst = <InlinableString as StringExt>::with_capacity(100);
+        let ptr = dst.as_ptr();
+        let capacity = InlinableString::capacity(&dst);
+        dst.clone_from(&InlinableString::from(long));
+        assert_eq!(dst, long);
+        assert_eq!(dst.as_ptr(), ptr);
+        assert_eq!(InlinableString::capacity(&dst), capacity);
+
+        dst.clone_from(&InlinableString::from("small"));
+        assert_eq!(dst, "small");
+        assert!(dst.is_heap());
+        assert_eq!(dst.as_ptr(), ptr);
+        assert_eq!(InlinableString::capacity(&dst), capacity);
+
+        let mut dst = InlinableString::from(long);
+        dst.clone_from(&InlinableString::from("x".repeat(200)));
+        assert_eq!(dst, "x".repeat(200));
+
+        let mut dst = InlinableString::from("small");
+        dst.clone_from(&InlinableString::from("other"));
+        assert_eq!(dst, "other");
+        assert!(dst.is_inline());
+
+        dst.clone_from(&InlinableString::from(long));
+        assert_eq!(dst, long);
+        assert!(dst.is_heap());
     }
 
     #[test]
-    fn test_from_utf8() {
-        let s = <InlinableString as StringExt>::from_utf8(vec![104, 101, 108, 108, 111]);
-        assert_eq!(s.unwrap(), "hello");
+    fn test_into_bytes_inlined() {
+        let bytes = InlinableString::from("small").into_bytes_inlined();
+        assert!(bytes.is_inline());
+        assert_eq!(bytes, &b"small"[..]);
+
+        let long = "a string that is too long to be stored inline";
+        let s = InlinableString::from(long);
+        let ptr = s.as_ptr();
+        let bytes = s.into_bytes_inlined();
+        assert!(bytes.is_heap());
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(bytes.into_vec(), long.as_bytes());
     }
 
     #[test]
-    fn test_from_utf16() {
-        let v = &mut [0xD834, 0xDD1E, 0x006d, 0x0075,
-                      0x0073, 0x0069, 0x0063];
-        let s = <InlinableString as StringExt>::from_utf16(v);
-        assert_eq!(s.unwrap(), "𝄞music");
+    fn test_clear_keeps_capacity() {
+        let long = "a string that is too long to be stored inline";
+        let mut s = InlinableString::from(long);
+        let capacity = InlinableString::capacity(&s);
+        s.clear();
+        assert!(s.is_empty());
+        assert!(s.is_heap());
+        assert_eq!(InlinableString::capacity(&s), capacity);
+        s.push_str(long);
+        assert_eq!(s, long);
+        assert_eq!(InlinableString::capacity(&s), capacity);
+
+        let mut s = InlinableString::from("small");
+        s.clear();
+        assert!(s.is_empty());
+        assert!(s.is_inline());
     }
 
     #[test]
-    fn test_from_utf16_lossy() {
-        let input = b"Hello \xF0\x90\x80World";
-        let output = <InlinableString as StringExt>::from_utf8_lossy(input);
-        assert_eq!(output, "Hello \u{FFFD}World");
+    fn test_reset_to_inline() {
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        s.reset_to_inline();
+        assert!(s.is_empty());
+        assert!(s.is_inline());
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+
+        let mut s = InlinableString::from("small");
+        s.reset_to_inline();
+        assert_eq!(s, "");
+        assert!(s.is_inline());
     }
 
     #[test]
-    fn test_into_bytes() {
-        let s = InlinableString::from("hello");
-        let bytes = StringExt::into_bytes(s);
-        assert_eq!(bytes, [104, 101, 108, 108, 111]);
+    fn test_push_within_capacity() {
+        let mut s = InlinableString::new();
+        for _ in 0..INLINE_STRING_CAPACITY {
+            assert!(s.push_within_capacity('a').is_ok());
+        }
+        assert!(s.push_within_capacity('a').is_err());
+        assert!(s.is_inline());
+        assert_eq!(s, "a".repeat(INLINE_STRING_CAPACITY));
+
+        let mut s = InlinableString::from("a".repeat(INLINE_STRING_CAPACITY - 1));
+        assert!(s.push_within_capacity('é').is_err());
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_STRING_CAPACITY - 1);
+
+        let mut s = <InlinableString as StringExt>::with_capacity(INLINE_STRING_CAPACITY + 2);
+        let capacity = InlinableString::capacity(&s);
+        s.push_str(&"a".repeat(capacity - 1));
+        assert!(s.push_within_capacity('é').is_err());
+        assert!(s.push_within_capacity('b').is_ok());
+        assert!(s.push_within_capacity('c').is_err());
+        assert!(s.is_heap());
+        assert_eq!(InlinableString::capacity(&s), capacity);
+        assert!(s.ends_with("ab"));
     }
 
     #[test]
-    fn test_capacity() {
-        let s = <InlinableString as StringExt>::with_capacity(100);
-        assert!(InlinableString::capacity(&s) >= 100);
+    fn test_push_str_within_capacity() {
+        let mut s = InlinableString::from("foo");
+        assert!(s.push_str_within_capacity("bar").is_ok());
+        assert_eq!(s, "foobar");
+
+        let long = "a string that is too long to be stored inline";
+        assert!(s.push_str_within_capacity(long).is_err());
+        assert!(s.is_inline());
+        assert_eq!(s, "foobar");
+
+        let mut s = <InlinableString as StringExt>::with_capacity(100);
+        let capacity = InlinableString::capacity(&s);
+        assert!(s.push_str_within_capacity(long).is_ok());
+        assert!(s.push_str_within_capacity(&"x".repeat(capacity - long.len() + 1)).is_err());
+        assert_eq!(s, long);
+        assert!(s.push_str_within_capacity(&"x".repeat(capacity - long.len())).is_ok());
+        assert_eq!(s.len(), capacity);
+        assert_eq!(InlinableString::capacity(&s), capacity);
+        assert!(s.push_str_within_capacity("").is_ok());
     }
 
     #[test]
-    fn test_reserve() {
-        let mut s = <InlinableString as StringExt>::new();
-        StringExt::reserve(&mut s, 100);
-        assert!(InlinableString::capacity(&s) >= 100);
+    fn test_promotion_capacity() {
+        let full = "a".repeat(INLINE_STRING_CAPACITY);
+
+        let mut s = InlinableString::from(&full[..]);
+        s.push('b');
+        assert!(s.is_heap());
+        assert_eq!(InlinableString::capacity(&s), 2 * INLINE_STRING_CAPACITY);
+        let ptr = s.as_ptr();
+        for _ in s.len()..2 * INLINE_STRING_CAPACITY {
+            s.push('c');
+        }
+        assert_eq!(s.as_ptr(), ptr);
+
+        // Promoting for a multi-byte char allocates once, with room for the
+        // whole char.
+        let mut s = InlinableString::from(&full[..]);
+        s.insert(0, '𝄞');
+        assert_eq!(InlinableString::capacity(&s), 2 * INLINE_STRING_CAPACITY);
+        assert!(s.starts_with('𝄞'));
+        assert!(s.ends_with(&full[..]));
+
+        let mut s = InlinableString::from(&full[..]);
+        s.insert_str(1, "bc");
+        assert_eq!(InlinableString::capacity(&s), 2 * INLINE_STRING_CAPACITY);
+
+        let mut s = InlinableString::from(&full[..]);
+        s.extend_from_within(..2);
+        assert_eq!(InlinableString::capacity(&s), 2 * INLINE_STRING_CAPACITY);
+
+        let mut s = InlinableString::from(&full[..]);
+        s.replace_range(..1, "bc");
+        assert_eq!(InlinableString::capacity(&s), 2 * INLINE_STRING_CAPACITY);
+        assert!(s.starts_with("bca"));
+
+        // Requests beyond the headroom get exactly what they need.
+        let long = "x".repeat(3 * INLINE_STRING_CAPACITY);
+        let mut s = InlinableString::from("foo");
+        s.push_str(&long);
+        assert_eq!(InlinableString::capacity(&s), 3 + long.len());
     }
 
     #[test]
-    fn test_reserve_exact() {
-        let mut s = <InlinableString as StringExt>::new();
-        StringExt::reserve_exact(&mut s, 100);
-        assert!(InlinableString::capacity(&s) >= 100);
+    fn test_try_promote() {
+        let s = InlineString::from("foo");
+        let promoted = super::try_promote(&s, 100, String::try_reserve_exact).unwrap();
+        assert_eq!(promoted, "foo");
+        assert_eq!(promoted.capacity(), 100);
+
+        // A failing allocator is reported without touching the source.
+        let err = String::new().try_reserve(usize::MAX).unwrap_err();
+        let result = super::try_promote(&s, 100, |_, _| Err(err.clone()));
+        assert_eq!(result, Err(err));
+        assert_eq!(s, "foo");
+
+        // The same holds end to end when promotion genuinely fails.
+        let mut s = InlinableString::from("foo");
+        assert!(StringExt::try_reserve(&mut s, usize::MAX).is_err());
+        assert_eq!(s, "foo");
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        assert!(s.try_push('a').is_ok());
+        assert_eq!(s, "fooa");
     }
 
     #[test]
@@ -782,6 +5764,29 @@ mod tests {
         assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
     }
 
+    #[test]
+    fn test_shrink_to() {
+        let mut s = <InlinableString as StringExt>::with_capacity(100);
+        StringExt::push_str(&mut s, "foo");
+        StringExt::shrink_to(&mut s, 50);
+        assert_eq!(InlinableString::capacity(&s), 50);
+        StringExt::shrink_to(&mut s, INLINE_STRING_CAPACITY);
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        assert_eq!(s, "foo");
+
+        StringExt::shrink_to(&mut s, 0);
+        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        assert_eq!(s, "foo");
+
+        let long_str = "this is a really long string that is much larger than
+                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+        let mut s = <InlinableString as StringExt>::with_capacity(1000);
+        StringExt::push_str(&mut s, long_str);
+        StringExt::shrink_to(&mut s, 0);
+        assert_eq!(InlinableString::capacity(&s), long_str.len());
+        assert_eq!(s, long_str);
+    }
+
     #[test]
     fn test_truncate() {
         let mut s = InlinableString::from("foo");
@@ -805,6 +5810,49 @@ mod tests {
         assert_eq!(Ord::cmp(&s1, &s2), Ordering::Greater);
         assert_eq!(Ord::cmp(&s1, &s1), Ordering::Equal);
     }
+
+    #[test]
+    fn test_ord_ignores_storage() {
+        let samples = ["", "a", "b", "ab", "é", "z", "a string that is too long to be stored inline"];
+        for &x in &samples {
+            for &y in &samples {
+                let expected = x.cmp(y);
+                let string = String::from(y);
+                let inline = InlinableString::from(x);
+                let heap = InlinableString::from_string_buffer(String::from(y));
+                assert_eq!(inline.cmp(&heap), expected);
+                assert_eq!(heap.cmp(&inline), expected.reverse());
+                assert_eq!(inline.partial_cmp(y), Some(expected));
+                assert_eq!(inline.partial_cmp(&y), Some(expected));
+                assert_eq!(inline.partial_cmp(&string), Some(expected));
+                assert_eq!(y.partial_cmp(&inline), Some(expected.reverse()));
+                assert_eq!(string.partial_cmp(&inline), Some(expected.reverse()));
+            }
+        }
+        let a = InlinableString::from("a");
+        let b = String::from("b");
+        assert!(a < "b");
+        assert!(b > a);
+    }
+
+    #[test]
+    fn test_sort_and_btree_map() {
+        use std::collections::BTreeMap;
+
+        let long_str = "a string that is too long to be stored inline";
+        let mut v: Vec<InlinableString> = vec!["pear", long_str, "apple", "fig"]
+            .into_iter().map(InlinableString::from).collect();
+        v.sort();
+        assert_eq!(v, [long_str, "apple", "fig", "pear"]);
+        assert_eq!(v.iter().max().unwrap(), "pear");
+
+        let mut map = BTreeMap::new();
+        map.insert(InlinableString::from("apple"), 1);
+        map.insert(InlinableString::from(long_str), 2);
+        assert_eq!(map.get("apple"), Some(&1));
+        assert_eq!(map.get(long_str), Some(&2));
+        assert_eq!(map.get("pear"), None);
+    }
     
     #[test]
     fn test_display() {
@@ -821,6 +5869,12 @@ mod tests {
         assert_eq!(format!("{:?}", short), "\"he\"");
         assert_eq!(format!("{:?}", long), "\"hello world hello world hello world\"");
     }
+
+    mod conformance {
+        use InlinableString;
+
+        string_ext_conformance_tests!(InlinableString);
+    }
 }
 
 #[cfg(test)]
@@ -929,6 +5983,118 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_std_string_push_1000_chars(b: &mut Bencher) {
+        b.iter(|| {
+            let mut s = String::new();
+            for _ in 0..1000 {
+                s.push(black_box('a'));
+            }
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_push_1000_chars(b: &mut Bencher) {
+        b.iter(|| {
+            let mut s = InlinableString::new();
+            for _ in 0..1000 {
+                s.push(black_box('a'));
+            }
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_std_string_reserve_and_push_1000_chars(b: &mut Bencher) {
+        b.iter(|| {
+            let mut s = String::new();
+            for _ in 0..1000 {
+                s.reserve(1);
+                s.push(black_box('a'));
+            }
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_reserve_and_push_1000_chars(b: &mut Bencher) {
+        b.iter(|| {
+            let mut s = InlinableString::new();
+            for _ in 0..1000 {
+                s.reserve(1);
+                s.push(black_box('a'));
+            }
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_collect_repeated_char(b: &mut Bencher) {
+        b.iter(|| {
+            let s: InlinableString = ::std::iter::repeat(black_box('-')).take(100).collect();
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_from_char_repeated(b: &mut Bencher) {
+        b.iter(|| {
+            let s = InlinableString::from_char_repeated(black_box('-'), 100);
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_std_str_from_utf8_long(b: &mut Bencher) {
+        let long = LARGE_STR.repeat(64);
+        b.iter(|| {
+            let s = ::std::str::from_utf8(black_box(long.as_bytes())).map(String::from);
+            black_box(s)
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_from_utf8_slice_long(b: &mut Bencher) {
+        let long = LARGE_STR.repeat(64);
+        b.iter(|| {
+            let s = InlinableString::from_utf8_slice(black_box(long.as_bytes()));
+            black_box(s)
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_from_utf8_slice_small(b: &mut Bencher) {
+        b.iter(|| {
+            let s = InlinableString::from_utf8_slice(black_box(SMALL_STR.as_bytes()));
+            black_box(s)
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_from_ascii_small(b: &mut Bencher) {
+        b.iter(|| {
+            let s = InlinableString::from_ascii(black_box(SMALL_STR.as_bytes()));
+            black_box(s)
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_from_utf8_slice_large(b: &mut Bencher) {
+        b.iter(|| {
+            let s = InlinableString::from_utf8_slice(black_box(LARGE_STR.as_bytes()));
+            black_box(s)
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_from_ascii_large(b: &mut Bencher) {
+        b.iter(|| {
+            let s = InlinableString::from_ascii(black_box(LARGE_STR.as_bytes()));
+            black_box(s)
+        });
+    }
+
     #[bench]
     fn bench_std_string_from_large(b: &mut Bencher) {
         b.iter(|| {
@@ -13,17 +13,30 @@
 
 use std::borrow::{Borrow, Cow};
 use std::cmp::PartialEq;
-use std::fmt::Display;
+use std::collections::TryReserveError;
+use std::fmt::{self, Display};
+use std::iter::FusedIterator;
 use std::mem;
+use std::ops::{Bound, Deref, RangeBounds};
+use std::str;
 use std::string::{FromUtf8Error, FromUtf16Error};
 
 /// A trait that exists to abstract string operations over any number of
 /// concrete string type implementations.
 ///
+/// Implementors only need to provide `with_capacity`, `capacity`, `reserve`,
+/// `push_str`, `truncate` and `as_mut_slice`; every other method has a default
+/// implementation built on top of those and the `Borrow<str>` supertrait,
+/// which can be overridden where the concrete type can do better.
+///
+/// Because `Deref<Target = str>` is a supertrait, all of `str`'s methods
+/// (`contains`, `starts_with`, `trim`, ...) are available in generic code
+/// bounded only by `StringExt`.
+///
 /// See the [crate level documentation](./../index.html) for more.
-pub trait StringExt<'a>:
-    Borrow<str> + Display + PartialEq<str> + PartialEq<&'a str> + PartialEq<String> +
-    PartialEq<Cow<'a, str>>
+pub trait StringExt:
+    Borrow<str> + Deref<Target = str> + Display + PartialEq<str> + for<'a> PartialEq<&'a str> +
+    PartialEq<String> + for<'a> PartialEq<Cow<'a, str>>
 {
     /// Creates a new string buffer initialized with the empty string.
     ///
@@ -35,7 +48,9 @@ pub trait StringExt<'a>:
     /// let s = InlinableString::new();
     /// ```
     #[inline]
-    fn new() -> Self where Self: Sized;
+    fn new() -> Self where Self: Sized {
+        Self::with_capacity(0)
+    }
 
     /// Creates a new string buffer with the given capacity. The string will be
     /// able to hold at least `capacity` bytes without reallocating. If
@@ -52,6 +67,32 @@ pub trait StringExt<'a>:
     #[inline]
     fn with_capacity(capacity: usize) -> Self where Self: Sized;
 
+    /// Tries to create a new string buffer with the given capacity, returning
+    /// an error instead of aborting if the allocation fails. If `capacity` is
+    /// less than or equal to `INLINE_STRING_CAPACITY`, the string will not
+    /// heap allocate and this never fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::try_with_capacity(10).unwrap();
+    /// assert!(s.capacity() >= 10);
+    /// assert!(InlinableString::try_with_capacity(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> where Self: Sized {
+        let mut s = Self::new();
+        s.try_reserve_exact(capacity)?;
+        Ok(s)
+    }
+
     /// Returns the vector as a string buffer, if possible, taking care not to
     /// copy it.
     ///
@@ -75,7 +116,9 @@ pub trait StringExt<'a>:
     /// assert_eq!(s.into_bytes(), [240, 144, 128]);
     /// ```
     #[inline]
-    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error>  where Self: Sized;
+    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error>  where Self: Sized {
+        String::from_utf8(vec).map(|s| from_str(&s))
+    }
 
     /// Converts a vector of bytes to a new UTF-8 string.
     /// Any invalid UTF-8 sequences are replaced with U+FFFD REPLACEMENT CHARACTER.
@@ -89,10 +132,30 @@ pub trait StringExt<'a>:
     /// let output = InlinableString::from_utf8_lossy(input);
     /// assert_eq!(output, "Hello \u{FFFD}World");
     /// ```
-    fn from_utf8_lossy(v: &'a [u8]) -> Cow<'a, str> where Self: Sized {
+    fn from_utf8_lossy(v: &[u8]) -> Cow<'_, str> where Self: Sized {
         String::from_utf8_lossy(v)
     }
 
+    /// Converts a vector of bytes to a new UTF-8 string, reusing the vector's
+    /// allocation when the bytes are valid UTF-8. Any invalid UTF-8 sequences
+    /// are replaced with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let input = b"Hello \xF0\x90\x80World".to_vec();
+    /// let output = InlinableString::from_utf8_lossy_owned(input);
+    /// assert_eq!(output, "Hello \u{FFFD}World");
+    /// ```
+    #[inline]
+    fn from_utf8_lossy_owned(vec: Vec<u8>) -> Self where Self: Sized {
+        let mut s = Self::new();
+        s.push_utf8_lossy(&vec);
+        s
+    }
+
     /// Decode a UTF-16 encoded vector `v` into a `InlinableString`, returning `None`
     /// if `v` contains any invalid data.
     ///
@@ -111,7 +174,9 @@ pub trait StringExt<'a>:
     /// v[4] = 0xD800;
     /// assert!(InlinableString::from_utf16(v).is_err());
     /// ```
-    fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> where Self: Sized;
+    fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> where Self: Sized {
+        decode_utf16(v.iter().cloned())
+    }
 
     /// Decode a UTF-16 encoded vector `v` into a string, replacing
     /// invalid data with the replacement character (U+FFFD).
@@ -130,7 +195,113 @@ pub trait StringExt<'a>:
     ///            InlinableString::from("𝄞mus\u{FFFD}ic\u{FFFD}"));
     /// ```
     #[inline]
-    fn from_utf16_lossy(v: &[u16]) -> Self where Self: Sized;
+    fn from_utf16_lossy(v: &[u16]) -> Self where Self: Sized {
+        decode_utf16_lossy(v.iter().cloned())
+    }
+
+    /// Decode a UTF-16LE encoded byte slice `v` into a string, returning an
+    /// error if `v` contains any invalid data or has an odd length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// // 𝄞music
+    /// let v = &[0x34, 0xD8, 0x1E, 0xDD, 0x6d, 0x00, 0x75, 0x00,
+    ///           0x73, 0x00, 0x69, 0x00, 0x63, 0x00];
+    /// assert_eq!(InlinableString::from_utf16le(v).unwrap(),
+    ///            InlinableString::from("𝄞music"));
+    ///
+    /// // 𝄞mu<invalid>ic
+    /// let v = &[0x34, 0xD8, 0x1E, 0xDD, 0x6d, 0x00, 0x75, 0x00,
+    ///           0x00, 0xD8, 0x69, 0x00, 0x63, 0x00];
+    /// assert!(InlinableString::from_utf16le(v).is_err());
+    /// ```
+    fn from_utf16le(v: &[u8]) -> Result<Self, FromUtf16Error> where Self: Sized {
+        if !v.len().is_multiple_of(2) {
+            return Err(utf16_error());
+        }
+        decode_utf16(v.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])))
+    }
+
+    /// Decode a UTF-16LE encoded byte slice `v` into a string, replacing
+    /// invalid data with the replacement character (U+FFFD). A trailing odd
+    /// byte is also replaced with U+FFFD.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// // 𝄞mus<invalid>ic<invalid>
+    /// let v = &[0x34, 0xD8, 0x1E, 0xDD, 0x6d, 0x00, 0x75, 0x00,
+    ///           0x73, 0x00, 0x1E, 0xDD, 0x69, 0x00, 0x63, 0x00,
+    ///           0x34, 0xD8];
+    ///
+    /// assert_eq!(InlinableString::from_utf16le_lossy(v),
+    ///            InlinableString::from("𝄞mus\u{FFFD}ic\u{FFFD}"));
+    /// ```
+    fn from_utf16le_lossy(v: &[u8]) -> Self where Self: Sized {
+        let mut s: Self = decode_utf16_lossy(v.chunks_exact(2)
+                                              .map(|b| u16::from_le_bytes([b[0], b[1]])));
+        if !v.len().is_multiple_of(2) {
+            s.push(char::REPLACEMENT_CHARACTER);
+        }
+        s
+    }
+
+    /// Decode a UTF-16BE encoded byte slice `v` into a string, returning an
+    /// error if `v` contains any invalid data or has an odd length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// // 𝄞music
+    /// let v = &[0xD8, 0x34, 0xDD, 0x1E, 0x00, 0x6d, 0x00, 0x75,
+    ///           0x00, 0x73, 0x00, 0x69, 0x00, 0x63];
+    /// assert_eq!(InlinableString::from_utf16be(v).unwrap(),
+    ///            InlinableString::from("𝄞music"));
+    ///
+    /// // 𝄞mu<invalid>ic
+    /// let v = &[0xD8, 0x34, 0xDD, 0x1E, 0x00, 0x6d, 0x00, 0x75,
+    ///           0xD8, 0x00, 0x00, 0x69, 0x00, 0x63];
+    /// assert!(InlinableString::from_utf16be(v).is_err());
+    /// ```
+    fn from_utf16be(v: &[u8]) -> Result<Self, FromUtf16Error> where Self: Sized {
+        if !v.len().is_multiple_of(2) {
+            return Err(utf16_error());
+        }
+        decode_utf16(v.chunks_exact(2).map(|b| u16::from_be_bytes([b[0], b[1]])))
+    }
+
+    /// Decode a UTF-16BE encoded byte slice `v` into a string, replacing
+    /// invalid data with the replacement character (U+FFFD). A trailing odd
+    /// byte is also replaced with U+FFFD.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// // 𝄞mus<invalid>ic<invalid>
+    /// let v = &[0xD8, 0x34, 0xDD, 0x1E, 0x00, 0x6d, 0x00, 0x75,
+    ///           0x00, 0x73, 0xDD, 0x1E, 0x00, 0x69, 0x00, 0x63,
+    ///           0xD8, 0x34];
+    ///
+    /// assert_eq!(InlinableString::from_utf16be_lossy(v),
+    ///            InlinableString::from("𝄞mus\u{FFFD}ic\u{FFFD}"));
+    /// ```
+    fn from_utf16be_lossy(v: &[u8]) -> Self where Self: Sized {
+        let mut s: Self = decode_utf16_lossy(v.chunks_exact(2)
+                                              .map(|b| u16::from_be_bytes([b[0], b[1]])));
+        if !v.len().is_multiple_of(2) {
+            s.push(char::REPLACEMENT_CHARACTER);
+        }
+        s
+    }
 
     /// Creates a new `InlinableString` from a length, capacity, and pointer.
     ///
@@ -145,13 +316,17 @@ pub trait StringExt<'a>:
     ///
     /// * We assume that the `Vec` contains valid UTF-8.
     #[inline]
-    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self where Self: Sized;
+    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self where Self: Sized {
+        from_str(&String::from_raw_parts(buf, length, capacity))
+    }
 
     /// Converts a vector of bytes to a new `InlinableString` without checking
     /// if it contains valid UTF-8. This is unsafe because it assumes that the
     /// UTF-8-ness of the vector has already been validated.
     #[inline]
-    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self where Self: Sized;
+    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self where Self: Sized {
+        from_str(str::from_utf8_unchecked(&bytes))
+    }
 
     /// Returns the underlying byte buffer, encoded as UTF-8.
     ///
@@ -165,7 +340,71 @@ pub trait StringExt<'a>:
     /// assert_eq!(bytes, [104, 101, 108, 108, 111]);
     /// ```
     #[inline]
-    fn into_bytes(self) -> Vec<u8>;
+    fn into_bytes(self) -> Vec<u8> where Self: Sized {
+        self.as_bytes().to_vec()
+    }
+
+    /// Converts this string buffer into a `Box<str>`, dropping any excess
+    /// capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("hello");
+    /// let b = s.into_boxed_str();
+    /// assert_eq!(&*b, "hello");
+    /// ```
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> where Self: Sized {
+        Box::from(self.as_str())
+    }
+
+    /// Converts this string buffer into an iterator over its characters,
+    /// taking ownership of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("abç");
+    /// let mut chars = s.into_chars();
+    /// assert_eq!(chars.next(), Some('a'));
+    /// assert_eq!(chars.next_back(), Some('ç'));
+    /// assert_eq!(chars.as_str(), "b");
+    /// ```
+    #[inline]
+    fn into_chars(self) -> IntoChars<Self> where Self: Sized {
+        let back = self.len();
+        IntoChars {
+            string: self,
+            front: 0,
+            back,
+        }
+    }
+
+    /// Consumes and leaks the string buffer, returning a mutable reference to
+    /// its contents that lives for the rest of the program.
+    ///
+    /// Strings that are not already heap-allocated (for example, an inline
+    /// `InlinableString`) are copied into a new heap allocation first, so
+    /// leaking them always allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("hello");
+    /// let leaked: &'static mut str = s.leak();
+    /// assert_eq!(leaked, "hello");
+    /// ```
+    #[inline]
+    fn leak(self) -> &'static mut str where Self: Sized {
+        Box::leak(self.into_boxed_str())
+    }
 
     /// Pushes the given string onto this string buffer.
     ///
@@ -181,6 +420,29 @@ pub trait StringExt<'a>:
     #[inline]
     fn push_str(&mut self, string: &str);
 
+    /// Appends the given bytes onto this string buffer, replacing any invalid
+    /// UTF-8 sequences with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("foo");
+    /// s.push_utf8_lossy(b"bar\xF0\x90\x80baz");
+    /// assert_eq!(s, "foobar\u{FFFD}baz");
+    /// ```
+    #[inline]
+    fn push_utf8_lossy(&mut self, bytes: &[u8]) {
+        self.reserve(bytes.len());
+        for chunk in bytes.utf8_chunks() {
+            self.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                self.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+    }
+
     /// Returns the number of bytes that this string buffer can hold without
     /// reallocating.
     ///
@@ -199,6 +461,12 @@ pub trait StringExt<'a>:
     /// in the given `InlinableString`. The collection may reserve more space to avoid
     /// frequent reallocations.
     ///
+    /// An `InlinableString` that can't fit the request inline is promoted to
+    /// the heap straight away: `capacity()` has to cover the request
+    /// afterwards, and an inline string has nowhere to remember a deferred
+    /// one. For a speculative size hint, `InlinableString::reserve_lazy`
+    /// leaves an inline string inline until an append overflows it.
+    ///
     /// # Panics
     ///
     /// Panics if the new capacity overflows `usize`.
@@ -237,7 +505,114 @@ pub trait StringExt<'a>:
     /// assert!(s.capacity() >= 10);
     /// ```
     #[inline]
-    fn reserve_exact(&mut self, additional: usize);
+    fn reserve_exact(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more bytes to be
+    /// inserted in the given string buffer. The collection may reserve more
+    /// space to avoid frequent reallocations.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned and the string buffer is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::new();
+    /// s.try_reserve(10).expect("why is the test harness OOMing on 10 bytes?");
+    /// assert!(s.capacity() >= 10);
+    /// assert!(s.try_reserve(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        // Without a fallible allocation primitive the best the default can do
+        // is reject sizes that no allocator could ever satisfy.
+        match self.len().checked_add(additional) {
+            Some(n) if n <= isize::MAX as usize => {
+                self.reserve(additional);
+                Ok(())
+            }
+            _ => Err(capacity_overflow()),
+        }
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more
+    /// bytes to be inserted in the given string buffer. Does nothing if the
+    /// capacity is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned and the string buffer is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::new();
+    /// s.try_reserve_exact(10).expect("why is the test harness OOMing on 10 bytes?");
+    /// assert!(s.capacity() >= 10);
+    /// assert!(s.try_reserve_exact(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_reserve(additional)
+    }
+
+    /// Appends the given `char` to the end of this string buffer, returning
+    /// an error instead of aborting if the allocator cannot provide the
+    /// memory.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation fails, an error is returned and the string buffer is
+    /// left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("abc");
+    /// s.try_push('1').expect("why is the test harness OOMing on 1 byte?");
+    /// assert_eq!(s, "abc1");
+    /// ```
+    #[inline]
+    fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
+        self.try_reserve(ch.len_utf8())?;
+        self.push(ch);
+        Ok(())
+    }
+
+    /// Pushes the given string onto this string buffer, returning an error
+    /// instead of aborting if the allocator cannot provide the memory.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation fails, an error is returned and the string buffer is
+    /// left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("foo");
+    /// s.try_push_str("bar").expect("why is the test harness OOMing on 3 bytes?");
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    fn try_push_str(&mut self, string: &str) -> Result<(), TryReserveError> {
+        self.try_reserve(string.len())?;
+        self.push_str(string);
+        Ok(())
+    }
 
     /// Shrinks the capacity of this string buffer to match its length. If the
     /// string's length is less than `INLINE_STRING_CAPACITY` and the string is
@@ -255,7 +630,33 @@ pub trait StringExt<'a>:
     /// assert_eq!(s.capacity(), inlinable_string::INLINE_STRING_CAPACITY);
     /// ```
     #[inline]
-    fn shrink_to_fit(&mut self);
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to(0)
+    }
+
+    /// Shrinks the capacity of this string buffer with a lower bound. The
+    /// capacity will remain at least as large as both the length and the
+    /// supplied value. If both the string's length and `min_capacity` are no
+    /// greater than `INLINE_STRING_CAPACITY` and the string is
+    /// heap-allocated, then it is demoted to inline storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("foo");
+    /// s.reserve(100);
+    /// assert!(s.capacity() >= 100);
+    /// s.shrink_to(50);
+    /// assert!(s.capacity() >= 50);
+    /// s.shrink_to(0);
+    /// assert_eq!(s.capacity(), inlinable_string::INLINE_STRING_CAPACITY);
+    /// ```
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        let _ = min_capacity;
+    }
 
     /// Adds the given character to the end of the string.
     ///
@@ -271,7 +672,54 @@ pub trait StringExt<'a>:
     /// assert_eq!(s, "abc123");
     /// ```
     #[inline]
-    fn push(&mut self, ch: char);
+    fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Extracts a string slice containing the entire string buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("foo");
+    /// assert_eq!(s.as_str(), "foo");
+    /// ```
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.borrow()
+    }
+
+    /// Copies the bytes in range `src` of the string buffer to its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("abcde");
+    /// s.extend_from_within(2..);
+    /// assert_eq!(s, "abcdecde");
+    /// s.extend_from_within(..2);
+    /// assert_eq!(s, "abcdecdeab");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the start or end of the range does not lie on a character boundary,
+    /// or if the range is out of bounds, then this function will panic.
+    #[inline]
+    fn extend_from_within<R>(&mut self, src: R) where R: RangeBounds<usize>, Self: Sized {
+        let (start, end) = char_range(self.as_str(), src);
+        self.reserve(end - start);
+        let mut idx = start;
+        while idx < end {
+            let ch = self.as_str()[idx..].chars().next().unwrap();
+            self.push(ch);
+            idx += ch.len_utf8();
+        }
+    }
 
     /// Works with the underlying buffer as a byte slice.
     ///
@@ -284,7 +732,9 @@ pub trait StringExt<'a>:
     /// assert_eq!(s.as_bytes(), [104, 101, 108, 108, 111]);
     /// ```
     #[inline]
-    fn as_bytes(&self) -> &[u8];
+    fn as_bytes(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
 
     /// Shortens a string to the specified length.
     ///
@@ -320,7 +770,12 @@ pub trait StringExt<'a>:
     /// assert_eq!(s.pop(), None);
     /// ```
     #[inline]
-    fn pop(&mut self) -> Option<char>;
+    fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        let new_len = self.len() - ch.len_utf8();
+        self.truncate(new_len);
+        Some(ch)
+    }
 
     /// Removes the character from the string buffer at byte position `idx` and
     /// returns it.
@@ -346,7 +801,18 @@ pub trait StringExt<'a>:
     /// assert_eq!(s.remove(0), 'o');
     /// ```
     #[inline]
-    fn remove(&mut self, idx: usize) -> char;
+    fn remove(&mut self, idx: usize) -> char {
+        let ch = match self.as_str()[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        let len = self.len();
+        unsafe {
+            self.as_mut_slice()[idx..].rotate_left(ch.len_utf8());
+        }
+        self.truncate(len - ch.len_utf8());
+        ch
+    }
 
     /// Inserts a character into the string buffer at byte position `idx`.
     ///
@@ -370,32 +836,257 @@ pub trait StringExt<'a>:
     /// If `idx` does not lie on a character boundary or is out of bounds, then
     /// this function will panic.
     #[inline]
-    fn insert(&mut self, idx: usize, ch: char);
+    fn insert(&mut self, idx: usize, ch: char) {
+        self.insert_str(idx, ch.encode_utf8(&mut [0; 4]))
+    }
 
-    /// Views the string buffer as a mutable sequence of bytes.
+    /// Inserts a string into the string buffer at byte position `idx`.
     ///
-    /// This is unsafe because it does not check to ensure that the resulting
-    /// string will be valid UTF-8.
+    /// # Warning
+    ///
+    /// This is an O(n) operation as it requires copying every element in the
+    /// buffer.
     ///
     /// # Examples
     ///
     /// ```
     /// use inlinable_string::{InlinableString, StringExt};
     ///
-    /// let mut s = InlinableString::from("hello");
-    /// unsafe {
-    ///     let slice = s.as_mut_slice();
-    ///     assert!(slice == &[104, 101, 108, 108, 111]);
-    ///     slice.reverse();
-    /// }
-    /// assert_eq!(s, "olleh");
+    /// let mut s = InlinableString::from("bar");
+    /// s.insert_str(0, "foo");
+    /// assert_eq!(s, "foobar");
     /// ```
-    #[inline]
-    unsafe fn as_mut_slice(&mut self) -> &mut [u8];
-
-    /// Returns the number of bytes in this string.
     ///
-    /// # Examples
+    /// # Panics
+    ///
+    /// If `idx` does not lie on a character boundary or is out of bounds, then
+    /// this function will panic.
+    #[inline]
+    fn insert_str(&mut self, idx: usize, string: &str) {
+        assert!(self.as_str().is_char_boundary(idx));
+        self.push_str(string);
+        unsafe {
+            // Both `string` and the tail it is rotated past are whole
+            // characters, so the result is still valid UTF-8.
+            self.as_mut_slice()[idx..].rotate_right(string.len());
+        }
+    }
+
+    /// Splits the string into two at the given byte index.
+    ///
+    /// Returns a new string containing bytes `[at, len)`, while `self` is left
+    /// containing bytes `[0, at)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("Hello, World!");
+    /// let world = s.split_off(7);
+    /// assert_eq!(s, "Hello, ");
+    /// assert_eq!(world, "World!");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `at` does not lie on a character boundary or is out of bounds, then
+    /// this function will panic.
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self where Self: Sized {
+        let other = from_str(&self.as_str()[at..]);
+        self.truncate(at);
+        other
+    }
+
+    /// Retains only the characters specified by the predicate.
+    ///
+    /// In other words, removes all characters `c` such that `f(c)` returns
+    /// `false`. This method operates in place and does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("f_o_ob_ar");
+    /// s.retain(|c| c != '_');
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    fn retain<F>(&mut self, mut f: F) where F: FnMut(char) -> bool, Self: Sized {
+        let len = self.len();
+        let mut guard = RetainGuard { string: self, read: 0, write: 0, len };
+        while guard.read < guard.len {
+            let ch = unsafe {
+                let rest = &guard.string.as_mut_slice()[guard.read..guard.len];
+                str::from_utf8_unchecked(rest).chars().next().unwrap()
+            };
+            let ch_len = ch.len_utf8();
+            if f(ch) {
+                unsafe {
+                    guard.string.as_mut_slice()
+                        .copy_within(guard.read..guard.read + ch_len, guard.write);
+                }
+                guard.write += ch_len;
+            }
+            guard.read += ch_len;
+        }
+    }
+
+    /// Removes all non-overlapping matches of `pat` from the string buffer,
+    /// scanning from left to right. The buffer is compacted in place, without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("Trees are not green, the sky is not blue.");
+    /// s.remove_matches("not ");
+    /// assert_eq!(s, "Trees are green, the sky is blue.");
+    ///
+    /// let mut s = InlinableString::from("aaaaa");
+    /// s.remove_matches("aa");
+    /// assert_eq!(s, "a");
+    /// ```
+    #[inline]
+    fn remove_matches(&mut self, pat: &str) {
+        if pat.is_empty() {
+            return;
+        }
+        let len = self.len();
+        let mut read = 0;
+        let mut write = 0;
+        unsafe {
+            let bytes = self.as_mut_slice();
+            while let Some(idx) = str::from_utf8_unchecked(&bytes[read..]).find(pat) {
+                bytes.copy_within(read..read + idx, write);
+                write += idx;
+                read += idx + pat.len();
+            }
+            bytes.copy_within(read..len, write);
+            write += len - read;
+            // Clear the leftover tail so the buffer remains valid UTF-8 up to
+            // the point where we truncate it.
+            for b in &mut bytes[write..] {
+                *b = 0;
+            }
+        }
+        self.truncate(write);
+    }
+
+    /// Removes the specified range from the string buffer in bulk, returning
+    /// all removed characters as an iterator.
+    ///
+    /// The removal happens when the returned iterator is dropped, whether or
+    /// not it has been fully consumed. If the iterator is leaked instead (for
+    /// example with `mem::forget`), the string is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("α is alpha, β is beta");
+    /// let beta_offset = s.find('β').unwrap();
+    ///
+    /// let t: String = s.drain(..beta_offset).collect();
+    /// assert_eq!(t, "α is alpha, ");
+    /// assert_eq!(s, "β is beta");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the start or end of the range does not lie on a character boundary,
+    /// or if the range is out of bounds, then this function will panic.
+    #[inline]
+    fn drain<R>(&mut self, range: R) -> Drain<'_, Self>
+        where R: RangeBounds<usize>, Self: Sized
+    {
+        let (start, end) = char_range(self.as_str(), range);
+        Drain {
+            string: self,
+            start,
+            end,
+            front: start,
+            back: end,
+        }
+    }
+
+    /// Removes the specified range in the string buffer, and replaces it with
+    /// the given string. The given string doesn't need to be the same length
+    /// as the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("α is alpha, β is beta");
+    /// let beta_offset = s.find('β').unwrap();
+    ///
+    /// s.replace_range(..beta_offset, "Α is capital alpha; ");
+    /// assert_eq!(s, "Α is capital alpha; β is beta");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the start or end of the range does not lie on a character boundary,
+    /// or if the range is out of bounds, then this function will panic.
+    #[inline]
+    fn replace_range<R>(&mut self, range: R, replace_with: &str)
+        where R: RangeBounds<usize>, Self: Sized
+    {
+        let (start, end) = char_range(self.as_str(), range);
+        self.drain(start..end);
+        self.insert_str(start, replace_with);
+    }
+
+    /// Views the string buffer as a mutable sequence of bytes.
+    ///
+    /// This is unsafe because it does not check to ensure that the resulting
+    /// string will be valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("hello");
+    /// unsafe {
+    ///     let slice = s.as_mut_slice();
+    ///     assert!(slice == &[104, 101, 108, 108, 111]);
+    ///     slice.reverse();
+    /// }
+    /// assert_eq!(s, "olleh");
+    /// ```
+    #[inline]
+    unsafe fn as_mut_slice(&mut self) -> &mut [u8];
+
+    /// Converts the string buffer into a mutable string slice.
+    ///
+    /// Unlike `as_mut_slice`, this is safe: a `&mut str` can only be used in
+    /// ways that keep its contents valid UTF-8 and its length unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("foobar");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(s, "FOOBAR");
+    /// ```
+    #[inline]
+    fn as_mut_str(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(self.as_mut_slice()) }
+    }
+
+    /// Returns the number of bytes in this string.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use inlinable_string::{InlinableString, StringExt};
@@ -404,7 +1095,9 @@ pub trait StringExt<'a>:
     /// assert_eq!(a.len(), 3);
     /// ```
     #[inline]
-    fn len(&self) -> usize;
+    fn len(&self) -> usize {
+        self.as_str().len()
+    }
 
     /// Returns true if the string contains no bytes
     ///
@@ -423,6 +1116,10 @@ pub trait StringExt<'a>:
 
     /// Truncates the string, returning it to 0 length.
     ///
+    /// Like `String::clear`, this keeps any allocated capacity, so an
+    /// `InlinableString` that has spilled to the heap stays there. See
+    /// `InlinableString::reset_to_inline` to release the allocation.
+    ///
     /// # Examples
     ///
     /// ```
@@ -436,18 +1133,320 @@ pub trait StringExt<'a>:
     fn clear(&mut self) { self.truncate(0); }
 }
 
-impl<'a> StringExt<'a> for String {
+/// Lets any `StringExt` implementation be the destination of the `write!`
+/// macro, which expands to a call to `write_fmt`.
+///
+/// This lives outside `StringExt` because `fmt::Write` also has a
+/// `write_fmt` method: were it provided by `StringExt`, importing both traits
+/// would make `write!` on a `String` ambiguous. Import this trait where
+/// `write!` is used in generic code bounded by `StringExt`, but not alongside
+/// `fmt::Write` for a `String`. `InlinableString` has an inherent `write_fmt`,
+/// so `write!` works on it whichever traits are in scope.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::{InlinableString, StringExt, StringExtWrite};
+///
+/// fn describe<S: StringExt>(out: &mut S, x: i32, y: f64) {
+///     write!(out, "x={}, y={:.1}", x, y).unwrap();
+/// }
+///
+/// let mut s = InlinableString::new();
+/// describe(&mut s, 1, 2.5);
+/// assert_eq!(s, "x=1, y=2.5");
+/// ```
+pub trait StringExtWrite: StringExt {
+    /// Writes formatted output onto the end of this string buffer.
+    #[inline]
+    fn write_fmt(&mut self, args: fmt::Arguments) -> fmt::Result {
+        if let Some(string) = args.as_str() {
+            self.push_str(string);
+            return Ok(());
+        }
+        fmt::write(&mut FmtAdapter(self), args)
+    }
+}
+
+impl<S: ?Sized + StringExt> StringExtWrite for S {}
+
+/// A draining iterator over a range of a `StringExt` implementation.
+///
+/// This struct is created by the
+/// [`drain`](./trait.StringExt.html#method.drain) method on `StringExt`. See
+/// its documentation for more.
+pub struct Drain<'a, S: 'a + StringExt> {
+    string: &'a mut S,
+    start: usize,
+    end: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, S: StringExt> Drain<'a, S> {
+    /// Returns the characters that have not been yielded yet as a string
+    /// slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("abc");
+    /// let mut drain = s.drain(..);
+    /// assert_eq!(drain.as_str(), "abc");
+    /// drain.next();
+    /// assert_eq!(drain.as_str(), "bc");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.string.as_str()[self.front..self.back]
+    }
+}
+
+impl<'a, S: StringExt> Iterator for Drain<'a, S> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.front += ch.len_utf8();
+        Some(ch)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len.div_ceil(4), Some(len))
+    }
+}
+
+impl<'a, S: StringExt> DoubleEndedIterator for Drain<'a, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl<'a, S: StringExt> FusedIterator for Drain<'a, S> {}
+
+impl<'a, S: StringExt> Drop for Drain<'a, S> {
+    fn drop(&mut self) {
+        let len = self.string.len();
+        let removed = self.end - self.start;
+        unsafe {
+            // Rotating the drained range to the end keeps every character's
+            // bytes together, so the buffer stays valid UTF-8 until we
+            // truncate it away.
+            self.string.as_mut_slice()[self.start..].rotate_left(removed);
+        }
+        self.string.truncate(len - removed);
+    }
+}
+
+/// An owning iterator over the characters of a `StringExt` implementation.
+///
+/// This struct is created by the
+/// [`into_chars`](./trait.StringExt.html#method.into_chars) method on
+/// `StringExt`. See its documentation for more.
+pub struct IntoChars<S: StringExt> {
+    string: S,
+    front: usize,
+    back: usize,
+}
+
+impl<S: StringExt> IntoChars<S> {
+    /// Returns the characters that have not been yielded yet as a string
+    /// slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut chars = InlinableString::from("abc").into_chars();
+    /// assert_eq!(chars.as_str(), "abc");
+    /// chars.next();
+    /// assert_eq!(chars.as_str(), "bc");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.string.as_str()[self.front..self.back]
+    }
+}
+
+impl<S: StringExt> Iterator for IntoChars<S> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.front += ch.len_utf8();
+        Some(ch)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len.div_ceil(4), Some(len))
+    }
+}
+
+impl<S: StringExt> DoubleEndedIterator for IntoChars<S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl<S: StringExt> FusedIterator for IntoChars<S> {}
+
+/// Lets `fmt::write` push formatted output onto any `StringExt` implementation.
+struct FmtAdapter<'s, S: 's + ?Sized>(&'s mut S);
+
+impl<'s, S: 's + ?Sized + StringExt> fmt::Write for FmtAdapter<'s, S> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.0.push(c);
+        Ok(())
+    }
+}
+
+/// Compacts the string when `retain` finishes, or when its predicate panics.
+/// Bytes in `write..read` are stale; everything from `read` on is untouched.
+struct RetainGuard<'s, S: 's + StringExt> {
+    string: &'s mut S,
+    read: usize,
+    write: usize,
+    len: usize,
+}
+
+impl<'s, S: StringExt> Drop for RetainGuard<'s, S> {
+    fn drop(&mut self) {
+        let new_len = self.write + (self.len - self.read);
+        unsafe {
+            let bytes = self.string.as_mut_slice();
+            bytes.copy_within(self.read..self.len, self.write);
+            // Clear the leftover tail so the buffer remains valid UTF-8 up to
+            // the point where we truncate it.
+            for b in &mut bytes[new_len..] {
+                *b = 0;
+            }
+        }
+        self.string.truncate(new_len);
+    }
+}
+
+/// Builds a new `S` holding a copy of `string`.
+#[inline]
+fn from_str<S: StringExt>(string: &str) -> S {
+    let mut s = S::with_capacity(string.len());
+    s.push_str(string);
+    s
+}
+
+/// Returns the error `try_reserve` reports when the requested capacity
+/// overflows.
+pub(crate) fn capacity_overflow() -> TryReserveError {
+    String::new().try_reserve(usize::MAX).unwrap_err()
+}
+
+/// Resolves `range` into a `(start, end)` pair of byte offsets into `string`.
+///
+/// # Panics
+///
+/// If the start or end of the range does not lie on a character boundary, or
+/// if the range is out of bounds, then this function will panic.
+pub(crate) fn char_range<R: RangeBounds<usize>>(string: &str, range: R) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(1)
+            .expect("attempted to index str from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n.checked_add(1)
+            .expect("attempted to index str up to maximum usize"),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => string.len(),
+    };
+
+    assert!(start <= end, "slice index starts at {} but ends at {}", start, end);
+    assert!(end <= string.len(),
+            "range end index {} out of range for str of length {}", end, string.len());
+    assert!(string.is_char_boundary(start));
+    assert!(string.is_char_boundary(end));
+
+    (start, end)
+}
+
+/// `FromUtf16Error` has no public constructor, so get one from std by decoding
+/// a lone surrogate.
+fn utf16_error() -> FromUtf16Error {
+    String::from_utf16(&[0xD800]).unwrap_err()
+}
+
+/// Decodes UTF-16 code units straight into a new `S`, without collecting them
+/// into an intermediate buffer first.
+fn decode_utf16<S, I>(units: I) -> Result<S, FromUtf16Error>
+    where S: StringExt, I: Iterator<Item = u16>
+{
+    // Every code unit decodes to at least one byte.
+    let mut s = S::with_capacity(units.size_hint().0);
+    for ch in char::decode_utf16(units) {
+        s.push(ch.map_err(|_| utf16_error())?);
+    }
+    Ok(s)
+}
+
+/// Like `decode_utf16`, but replaces invalid data with U+FFFD.
+fn decode_utf16_lossy<S, I>(units: I) -> S
+    where S: StringExt, I: Iterator<Item = u16>
+{
+    let mut s = S::with_capacity(units.size_hint().0);
+    for ch in char::decode_utf16(units) {
+        s.push(ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+    }
+    s
+}
+
+impl StringExt for String {
     #[inline]
     fn new() -> Self { String::new() }
 
     #[inline]
     fn with_capacity(capacity: usize) -> Self { String::with_capacity(capacity) }
 
+    #[inline]
+    fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let mut s = String::new();
+        s.try_reserve_exact(capacity)?;
+        Ok(s)
+    }
+
     #[inline]
     fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error> {
         String::from_utf8(vec)
     }
 
+    #[inline]
+    fn from_utf8_lossy_owned(vec: Vec<u8>) -> Self {
+        match String::from_utf8(vec) {
+            Ok(s) => s,
+            Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        }
+    }
+
     #[inline]
     fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> {
         String::from_utf16(v)
@@ -473,6 +1472,16 @@ impl<'a> StringExt<'a> for String {
         String::into_bytes(self)
     }
 
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> {
+        String::into_boxed_str(self)
+    }
+
+    #[inline]
+    fn leak(self) -> &'static mut str {
+        String::leak(self)
+    }
+
     #[inline]
     fn push_str(&mut self, string: &str) {
         String::push_str(self, string)
@@ -493,16 +1502,41 @@ impl<'a> StringExt<'a> for String {
         String::reserve_exact(self, additional)
     }
 
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        String::try_reserve(self, additional)
+    }
+
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        String::try_reserve_exact(self, additional)
+    }
+
     #[inline]
     fn shrink_to_fit(&mut self) {
         String::shrink_to_fit(self)
     }
 
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        String::shrink_to(self, min_capacity)
+    }
+
     #[inline]
     fn push(&mut self, ch: char) {
         String::push(self, ch)
     }
 
+    #[inline]
+    fn extend_from_within<R>(&mut self, src: R) where R: RangeBounds<usize> {
+        String::extend_from_within(self, src)
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        String::as_str(self)
+    }
+
     #[inline]
     fn as_bytes(&self) -> &[u8] {
         String::as_bytes(self)
@@ -528,17 +1562,243 @@ impl<'a> StringExt<'a> for String {
         String::insert(self, idx, ch)
     }
 
+    #[inline]
+    fn insert_str(&mut self, idx: usize, string: &str) {
+        String::insert_str(self, idx, string)
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        String::split_off(self, at)
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F) where F: FnMut(char) -> bool {
+        String::retain(self, f)
+    }
+
+    #[inline]
+    fn replace_range<R>(&mut self, range: R, replace_with: &str) where R: RangeBounds<usize> {
+        String::replace_range(self, range, replace_with)
+    }
+
     #[inline]
     unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
         mem::transmute(&mut **self)
     }
 
+    #[inline]
+    fn as_mut_str(&mut self) -> &mut str {
+        String::as_mut_str(self)
+    }
+
     #[inline]
     fn len(&self) -> usize { String::len(self) }
 }
 
-#[cfg(test)]
-mod std_string_stringext_sanity_tests {
+/// Read-only methods work on the borrowed string as-is, as do `truncate`,
+/// which just shortens the borrow, and `remove_matches` when nothing matches.
+/// Anything else that changes the contents converts it into an owned `String`
+/// first. Constructors wrap the `String` they build in `Cow::Owned`.
+impl<'a> StringExt for Cow<'a, str> {
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        Cow::Owned(String::with_capacity(capacity))
+    }
+
+    #[inline]
+    fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        <String as StringExt>::try_with_capacity(capacity).map(Cow::Owned)
+    }
+
+    #[inline]
+    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error> {
+        String::from_utf8(vec).map(Cow::Owned)
+    }
+
+    #[inline]
+    fn from_utf8_lossy_owned(vec: Vec<u8>) -> Self {
+        Cow::Owned(<String as StringExt>::from_utf8_lossy_owned(vec))
+    }
+
+    #[inline]
+    fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> {
+        String::from_utf16(v).map(Cow::Owned)
+    }
+
+    #[inline]
+    fn from_utf16_lossy(v: &[u16]) -> Self {
+        Cow::Owned(String::from_utf16_lossy(v))
+    }
+
+    #[inline]
+    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self {
+        Cow::Owned(String::from_raw_parts(buf, length, capacity))
+    }
+
+    #[inline]
+    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self {
+        Cow::Owned(String::from_utf8_unchecked(bytes))
+    }
+
+    #[inline]
+    fn into_bytes(self) -> Vec<u8> {
+        self.into_owned().into_bytes()
+    }
+
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> {
+        self.into_owned().into_boxed_str()
+    }
+
+    #[inline]
+    fn leak(self) -> &'static mut str {
+        self.into_owned().leak()
+    }
+
+    #[inline]
+    fn push_str(&mut self, string: &str) {
+        self.to_mut().push_str(string)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        match *self {
+            Cow::Borrowed(s) => s.len(),
+            Cow::Owned(ref s) => s.capacity(),
+        }
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.to_mut().reserve(additional)
+    }
+
+    #[inline]
+    fn reserve_exact(&mut self, additional: usize) {
+        self.to_mut().reserve_exact(additional)
+    }
+
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let owned = match *self {
+            Cow::Borrowed(s) => {
+                let mut owned = String::new();
+                owned.try_reserve(s.len().saturating_add(additional))?;
+                owned.push_str(s);
+                owned
+            },
+            Cow::Owned(ref mut s) => return s.try_reserve(additional),
+        };
+        *self = Cow::Owned(owned);
+        Ok(())
+    }
+
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let owned = match *self {
+            Cow::Borrowed(s) => {
+                let mut owned = String::new();
+                owned.try_reserve_exact(s.len().saturating_add(additional))?;
+                owned.push_str(s);
+                owned
+            },
+            Cow::Owned(ref mut s) => return s.try_reserve_exact(additional),
+        };
+        *self = Cow::Owned(owned);
+        Ok(())
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        if let Cow::Owned(ref mut s) = *self {
+            s.shrink_to_fit()
+        }
+    }
+
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        if let Cow::Owned(ref mut s) = *self {
+            s.shrink_to(min_capacity)
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, ch: char) {
+        self.to_mut().push(ch)
+    }
+
+    #[inline]
+    fn truncate(&mut self, new_len: usize) {
+        match *self {
+            // A shorter borrowed string is just a shorter borrow.
+            Cow::Borrowed(s) => {
+                if new_len < s.len() {
+                    *self = Cow::Borrowed(&s[..new_len]);
+                }
+            },
+            Cow::Owned(ref mut s) => s.truncate(new_len),
+        }
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<char> {
+        self.to_mut().pop()
+    }
+
+    #[inline]
+    fn remove(&mut self, idx: usize) -> char {
+        self.to_mut().remove(idx)
+    }
+
+    #[inline]
+    fn insert(&mut self, idx: usize, ch: char) {
+        self.to_mut().insert(idx, ch)
+    }
+
+    #[inline]
+    fn insert_str(&mut self, idx: usize, string: &str) {
+        self.to_mut().insert_str(idx, string)
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        Cow::Owned(self.to_mut().split_off(at))
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, f: F) where F: FnMut(char) -> bool {
+        self.to_mut().retain(f)
+    }
+
+    #[inline]
+    fn remove_matches(&mut self, pat: &str) {
+        if let Cow::Borrowed(s) = *self {
+            if pat.is_empty() || !s.contains(pat) {
+                return;
+            }
+        }
+        StringExt::remove_matches(self.to_mut(), pat)
+    }
+
+    #[inline]
+    fn replace_range<R>(&mut self, range: R, replace_with: &str) where R: RangeBounds<usize> {
+        self.to_mut().replace_range(range, replace_with)
+    }
+
+    #[inline]
+    unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.to_mut().as_mut_vec()
+    }
+
+    #[inline]
+    fn as_mut_str(&mut self) -> &mut str {
+        self.to_mut().as_mut_str()
+    }
+}
+
+#[cfg(test)]
+mod std_string_stringext_sanity_tests {
     // Sanity tests for std::string::String's StringExt implementation.
 
     use super::StringExt;
@@ -555,6 +1815,13 @@ mod std_string_stringext_sanity_tests {
         assert!(StringExt::capacity(&s) >= 10);
     }
 
+    #[test]
+    fn test_try_with_capacity() {
+        let s = <String as StringExt>::try_with_capacity(10).unwrap();
+        assert!(StringExt::capacity(&s) >= 10);
+        assert!(<String as StringExt>::try_with_capacity(usize::MAX).is_err());
+    }
+
     #[test]
     fn test_from_utf8() {
         let s = <String as StringExt>::from_utf8(vec![104, 101, 108, 108, 111]);
@@ -576,6 +1843,100 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(output, "Hello \u{FFFD}World");
     }
 
+    #[test]
+    fn test_from_utf16le() {
+        let v = &[0x34, 0xD8, 0x1E, 0xDD, 0x6d, 0x00, 0x75, 0x00];
+        let s = <String as StringExt>::from_utf16le(v);
+        assert_eq!(s.unwrap(), "𝄞mu");
+        assert!(<String as StringExt>::from_utf16le(&v[..7]).is_err());
+    }
+
+    #[test]
+    fn test_from_utf16le_lossy() {
+        let v = &[0x34, 0xD8, 0x6d, 0x00, 0x75];
+        let s = <String as StringExt>::from_utf16le_lossy(v);
+        assert_eq!(s, "\u{FFFD}m\u{FFFD}");
+    }
+
+    #[test]
+    fn test_from_utf16be() {
+        let v = &[0xD8, 0x34, 0xDD, 0x1E, 0x00, 0x6d, 0x00, 0x75];
+        let s = <String as StringExt>::from_utf16be(v);
+        assert_eq!(s.unwrap(), "𝄞mu");
+        assert!(<String as StringExt>::from_utf16be(&v[..7]).is_err());
+    }
+
+    #[test]
+    fn test_from_utf16be_lossy() {
+        let v = &[0xD8, 0x34, 0x00, 0x6d, 0x00];
+        let s = <String as StringExt>::from_utf16be_lossy(v);
+        assert_eq!(s, "\u{FFFD}m\u{FFFD}");
+    }
+
+    #[test]
+    fn test_from_utf8_lossy_owned() {
+        let v = b"hello".to_vec();
+        let ptr = v.as_ptr();
+        let s = <String as StringExt>::from_utf8_lossy_owned(v);
+        assert_eq!(s, "hello");
+        assert_eq!(s.as_ptr(), ptr);
+
+        let s = <String as StringExt>::from_utf8_lossy_owned(b"a\xFFb".to_vec());
+        assert_eq!(s, "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut s = String::from("a\r\nb\r\n");
+        StringExt::remove_matches(&mut s, "\r");
+        assert_eq!(s, "a\nb\n");
+
+        let mut s = String::from("aaaa");
+        StringExt::remove_matches(&mut s, "aa");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_into_chars() {
+        let mut chars = StringExt::into_chars(String::from("a𝄞b"));
+        assert_eq!(chars.next_back(), Some('b'));
+        assert_eq!(chars.as_str(), "a𝄞");
+        assert_eq!(chars.collect::<String>(), "a𝄞");
+    }
+
+    #[test]
+    fn test_push_utf8_lossy() {
+        let mut s = String::from("a");
+        StringExt::push_utf8_lossy(&mut s, b"b\xFFc\xE2\x82");
+        assert_eq!(s, "ab\u{FFFD}c\u{FFFD}");
+    }
+
+    #[test]
+    fn test_try_push() {
+        let mut s = String::from("ab");
+        assert!(StringExt::try_push(&mut s, 'c').is_ok());
+        assert!(StringExt::try_push_str(&mut s, "de").is_ok());
+        assert_eq!(s, "abcde");
+    }
+
+    #[test]
+    fn test_write_fmt() {
+        use super::StringExtWrite;
+        let mut s = String::from("a");
+        write!(s, "{}{:?}", 1, 2.5f32).unwrap();
+        write!(s, "literal").unwrap();
+        assert_eq!(s, "a12.5literal");
+    }
+
+    #[test]
+    fn test_write_with_fmt_write() {
+        // `StringExt` must not add a second `write_fmt` to `String`.
+        use std::fmt::Write;
+        let mut s = String::from("a");
+        write!(s, "{}", 1).unwrap();
+        assert_eq!(s, "a1");
+    }
+
     #[test]
     fn test_into_bytes() {
         let s = String::from("hello");
@@ -583,6 +1944,19 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(bytes, [104, 101, 108, 108, 111]);
     }
 
+    #[test]
+    fn test_into_boxed_str() {
+        let s = String::from("hello");
+        let b = StringExt::into_boxed_str(s);
+        assert_eq!(&*b, "hello");
+    }
+
+    #[test]
+    fn test_leak() {
+        let leaked = StringExt::leak(String::from("hello"));
+        assert_eq!(leaked, "hello");
+    }
+
     #[test]
     fn test_push_str() {
         let mut s = String::from("hello");
@@ -610,6 +1984,22 @@ mod std_string_stringext_sanity_tests {
         assert!(String::capacity(&s) >= 100);
     }
 
+    #[test]
+    fn test_try_reserve() {
+        let mut s = <String as StringExt>::new();
+        assert!(StringExt::try_reserve(&mut s, 100).is_ok());
+        assert!(String::capacity(&s) >= 100);
+        assert!(StringExt::try_reserve(&mut s, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_try_reserve_exact() {
+        let mut s = <String as StringExt>::new();
+        assert!(StringExt::try_reserve_exact(&mut s, 100).is_ok());
+        assert!(String::capacity(&s) >= 100);
+        assert!(StringExt::try_reserve_exact(&mut s, usize::MAX).is_err());
+    }
+
     #[test]
     fn test_shrink_to_fit() {
         let mut s = <String as StringExt>::with_capacity(100);
@@ -618,6 +2008,14 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(String::capacity(&s), 3);
     }
 
+    #[test]
+    fn test_shrink_to() {
+        let mut s = <String as StringExt>::with_capacity(100);
+        StringExt::push_str(&mut s, "foo");
+        StringExt::shrink_to(&mut s, 50);
+        assert_eq!(String::capacity(&s), 50);
+    }
+
     #[test]
     fn test_push() {
         let mut s = String::new();
@@ -625,6 +2023,13 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(s, "a");
     }
 
+    #[test]
+    fn test_extend_from_within() {
+        let mut s = String::from("foo");
+        StringExt::extend_from_within(&mut s, 1..);
+        assert_eq!(s, "foooo");
+    }
+
     #[test]
     fn test_truncate() {
         let mut s = String::from("foo");
@@ -632,6 +2037,57 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(s, "f");
     }
 
+    #[test]
+    fn test_insert_str() {
+        let mut s = String::from("bar");
+        StringExt::insert_str(&mut s, 0, "foo");
+        assert_eq!(s, "foobar");
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut s = String::from("foobar");
+        let t = StringExt::split_off(&mut s, 3);
+        assert_eq!(s, "foo");
+        assert_eq!(t, "bar");
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut s = String::from("f_o_ob_ar");
+        StringExt::retain(&mut s, |c| c != '_');
+        assert_eq!(s, "foobar");
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut s = String::from("foobar");
+        let t: String = StringExt::drain(&mut s, 1..4).collect();
+        assert_eq!(s, "far");
+        assert_eq!(t, "oob");
+    }
+
+    #[test]
+    fn test_replace_range() {
+        let mut s = String::from("foobar");
+        StringExt::replace_range(&mut s, 1..3, "ubar ");
+        assert_eq!(s, "fubar bar");
+    }
+
+    #[test]
+    fn test_as_mut_str() {
+        let mut s = String::from("foo");
+        StringExt::as_mut_str(&mut s).make_ascii_uppercase();
+        assert_eq!(s, "FOO");
+    }
+
+    #[test]
+    fn test_as_str() {
+        let s = String::from("foo");
+        let t: &dyn StringExt = &s;
+        assert_eq!(t.as_str(), "foo");
+    }
+
     #[test]
     fn test_pop() {
         let mut s = String::from("foo");
@@ -641,3 +2097,480 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(StringExt::pop(&mut s), None);
     }
 }
+
+#[cfg(test)]
+mod std_string_conformance_tests {
+    string_ext_conformance_tests!(String);
+}
+
+#[cfg(test)]
+mod cow_conformance_tests {
+    use std::borrow::Cow;
+
+    string_ext_conformance_tests!(Cow<'static, str>);
+}
+
+#[cfg(test)]
+mod cow_stringext_sanity_tests {
+    // Sanity tests for Cow<str>'s StringExt implementation.
+
+    use super::StringExt;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_read_only_does_not_clone() {
+        let original = "héllo wörld";
+        let mut s = Cow::Borrowed(original);
+        {
+            let t: &mut dyn StringExt = &mut s;
+            assert_eq!(t.len(), 13);
+            assert!(!t.is_empty());
+            assert_eq!(t.capacity(), 13);
+            assert_eq!(t.as_str(), original);
+            assert_eq!(t.as_bytes(), original.as_bytes());
+            assert!(t.contains("wö"));
+            t.shrink_to_fit();
+            t.shrink_to(0);
+        }
+        assert_eq!(s.as_ptr(), original.as_ptr());
+        assert!(matches!(s, Cow::Borrowed(_)));
+
+        let chars: String = s.clone().into_chars().collect();
+        assert_eq!(chars, original);
+        assert!(matches!(s, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_mutation_converts_once() {
+        let original = "foo";
+        let mut s = Cow::Borrowed(original);
+        StringExt::reserve(&mut s, 100);
+        assert!(matches!(s, Cow::Owned(_)));
+        let ptr = s.as_ptr();
+        assert!(ptr != original.as_ptr());
+
+        {
+            let t: &mut dyn StringExt = &mut s;
+            t.push_str("bar");
+            t.push('!');
+            t.insert(0, '>');
+            t.insert_str(1, "> ");
+            assert_eq!(t.pop(), Some('!'));
+            assert_eq!(t.remove(0), '>');
+            t.truncate(7);
+        }
+        assert_eq!(s, "> fooba");
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(original, "foo");
+    }
+
+    #[test]
+    fn test_mutations() {
+        let mut s = Cow::Borrowed("f_o_obar");
+        StringExt::retain(&mut s, |c| c != '_');
+        assert_eq!(s, "foobar");
+
+        let mut s = Cow::Borrowed("foobar");
+        let t = StringExt::split_off(&mut s, 3);
+        assert_eq!(s, "foo");
+        assert_eq!(t, "bar");
+
+        let mut s = Cow::Borrowed("foobar");
+        StringExt::replace_range(&mut s, 1..3, "ubar ");
+        assert_eq!(s, "fubar bar");
+
+        let mut s = Cow::Borrowed("foo");
+        StringExt::as_mut_str(&mut s).make_ascii_uppercase();
+        assert_eq!(s, "FOO");
+
+        let mut s = Cow::Borrowed("foobar");
+        let d: String = StringExt::drain(&mut s, 1..4).collect();
+        assert_eq!((d.as_str(), s.as_ref()), ("oob", "far"));
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut s = Cow::Borrowed("foo");
+        assert!(StringExt::try_reserve(&mut s, usize::MAX).is_err());
+        assert!(matches!(s, Cow::Borrowed(_)));
+        assert!(StringExt::try_reserve_exact(&mut s, usize::MAX).is_err());
+        assert!(matches!(s, Cow::Borrowed(_)));
+
+        assert!(StringExt::try_reserve_exact(&mut s, 10).is_ok());
+        assert!(matches!(s, Cow::Owned(_)));
+        assert!(StringExt::capacity(&s) >= 13);
+        assert!(StringExt::try_reserve(&mut s, 100).is_ok());
+        assert_eq!(s, "foo");
+    }
+
+    #[test]
+    fn test_into() {
+        assert_eq!(StringExt::into_bytes(Cow::Borrowed("foo")), b"foo");
+        assert_eq!(&*StringExt::into_boxed_str(Cow::Borrowed("foo")), "foo");
+        assert_eq!(StringExt::leak(Cow::Borrowed("foo")), "foo");
+    }
+
+    #[test]
+    fn test_constructors() {
+        let s = <Cow<str> as StringExt>::with_capacity(10);
+        assert!(matches!(s, Cow::Owned(_)));
+        assert!(StringExt::capacity(&s) >= 10);
+
+        let s = <Cow<str> as StringExt>::from_utf8_lossy_owned(b"a\xFFb".to_vec());
+        assert_eq!(s, "a\u{FFFD}b");
+
+        // Constructors that produce a `String` keep its buffer.
+        let v = b"hello".to_vec();
+        let ptr = v.as_ptr();
+        let s = <Cow<str> as StringExt>::from_utf8(v).unwrap();
+        assert!(matches!(s, Cow::Owned(_)));
+        assert_eq!(s.as_ptr(), ptr);
+        assert!(<Cow<str> as StringExt>::from_utf8(vec![0xFF]).is_err());
+
+        let v = b"hello".to_vec();
+        let ptr = v.as_ptr();
+        let s = unsafe { <Cow<str> as StringExt>::from_utf8_unchecked(v) };
+        assert_eq!(s, "hello");
+        assert_eq!(s.as_ptr(), ptr);
+
+        let mut v = String::from("hello").into_bytes();
+        let (ptr, len, cap) = (v.as_mut_ptr(), v.len(), v.capacity());
+        ::std::mem::forget(v);
+        let s = unsafe { <Cow<str> as StringExt>::from_raw_parts(ptr, len, cap) };
+        assert_eq!(s, "hello");
+        assert_eq!(s.as_ptr(), ptr as *const u8);
+
+        let s = <Cow<str> as StringExt>::from_utf16(&[0x68, 0x69]).unwrap();
+        assert!(matches!(s, Cow::Owned(_)));
+        assert_eq!(s, "hi");
+        assert_eq!(<Cow<str> as StringExt>::from_utf16_lossy(&[0x68, 0xD800]), "h\u{FFFD}");
+
+        let s = <Cow<str> as StringExt>::try_with_capacity(10).unwrap();
+        assert!(StringExt::capacity(&s) >= 10);
+    }
+
+    #[test]
+    fn test_no_op_mutations_do_not_clone() {
+        let original = "foobar";
+        let mut s = Cow::Borrowed(original);
+        StringExt::truncate(&mut s, 10);
+        StringExt::truncate(&mut s, 6);
+        StringExt::remove_matches(&mut s, "x");
+        StringExt::remove_matches(&mut s, "");
+        assert!(matches!(s, Cow::Borrowed(_)));
+        assert_eq!(s.as_ptr(), original.as_ptr());
+
+        StringExt::truncate(&mut s, 3);
+        assert!(matches!(s, Cow::Borrowed(_)));
+        assert_eq!(s, "foo");
+
+        StringExt::remove_matches(&mut s, "o");
+        assert!(matches!(s, Cow::Owned(_)));
+        assert_eq!(s, "f");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_truncate_borrowed_inside_char() {
+        let mut s = Cow::Borrowed("é");
+        StringExt::truncate(&mut s, 1);
+    }
+}
+
+#[cfg(test)]
+mod default_impl_tests {
+    // Exercises the provided methods through a type that only implements the
+    // required core of `StringExt`.
+
+    use super::{StringExt, StringExtWrite};
+    use std::borrow::{Borrow, Cow};
+    use std::fmt;
+    use std::ops::Deref;
+    use std::str;
+
+    #[derive(Debug)]
+    struct MinimalString {
+        bytes: Vec<u8>,
+    }
+
+    impl Borrow<str> for MinimalString {
+        fn borrow(&self) -> &str {
+            unsafe { str::from_utf8_unchecked(&self.bytes) }
+        }
+    }
+
+    impl Deref for MinimalString {
+        type Target = str;
+
+        fn deref(&self) -> &str {
+            self.borrow()
+        }
+    }
+
+    impl fmt::Display for MinimalString {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.as_str().fmt(f)
+        }
+    }
+
+    impl PartialEq<str> for MinimalString {
+        fn eq(&self, other: &str) -> bool {
+            self.as_str() == other
+        }
+    }
+
+    impl<'a> PartialEq<&'a str> for MinimalString {
+        fn eq(&self, other: &&'a str) -> bool {
+            self.as_str() == *other
+        }
+    }
+
+    impl PartialEq<String> for MinimalString {
+        fn eq(&self, other: &String) -> bool {
+            self.as_str() == other
+        }
+    }
+
+    impl<'a> PartialEq<Cow<'a, str>> for MinimalString {
+        fn eq(&self, other: &Cow<'a, str>) -> bool {
+            self.as_str() == other
+        }
+    }
+
+    impl StringExt for MinimalString {
+        fn with_capacity(capacity: usize) -> Self {
+            MinimalString { bytes: Vec::with_capacity(capacity) }
+        }
+
+        fn capacity(&self) -> usize {
+            self.bytes.capacity()
+        }
+
+        fn reserve(&mut self, additional: usize) {
+            self.bytes.reserve(additional)
+        }
+
+        fn push_str(&mut self, string: &str) {
+            self.bytes.extend_from_slice(string.as_bytes())
+        }
+
+        fn truncate(&mut self, new_len: usize) {
+            if new_len <= self.len() {
+                assert!(self.as_str().is_char_boundary(new_len));
+                self.bytes.truncate(new_len)
+            }
+        }
+
+        unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+            &mut self.bytes
+        }
+    }
+
+    fn from(s: &str) -> MinimalString {
+        let mut m = MinimalString::new();
+        m.push_str(s);
+        m
+    }
+
+    #[test]
+    fn test_new() {
+        let s = MinimalString::new();
+        assert!(s.is_empty());
+        assert_eq!(s.len(), 0);
+    }
+
+    #[test]
+    fn test_try_with_capacity() {
+        let s = MinimalString::try_with_capacity(10).unwrap();
+        assert!(s.capacity() >= 10);
+        assert!(MinimalString::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_from_utf8() {
+        assert_eq!(MinimalString::from_utf8(b"hello".to_vec()).unwrap(), "hello");
+        assert!(MinimalString::from_utf8(b"\xFF".to_vec()).is_err());
+        assert_eq!(MinimalString::from_utf8_lossy_owned(b"a\xFFb".to_vec()), "a\u{FFFD}b");
+        assert_eq!(unsafe { MinimalString::from_utf8_unchecked(b"abc".to_vec()) }, "abc");
+    }
+
+    #[test]
+    fn test_from_raw_parts() {
+        let mut v = b"hello".to_vec();
+        let (ptr, len, cap) = (v.as_mut_ptr(), v.len(), v.capacity());
+        ::std::mem::forget(v);
+        let s = unsafe { MinimalString::from_raw_parts(ptr, len, cap) };
+        assert_eq!(s, "hello");
+    }
+
+    #[test]
+    fn test_from_utf16() {
+        let v = [0xD834, 0xDD1E, 0x006d, 0x0075];
+        assert_eq!(MinimalString::from_utf16(&v).unwrap(), "𝄞mu");
+        assert!(MinimalString::from_utf16(&v[..1]).is_err());
+        assert_eq!(MinimalString::from_utf16_lossy(&v[1..]), "\u{FFFD}mu");
+    }
+
+    #[test]
+    fn test_into() {
+        assert_eq!(from("hello").into_bytes(), b"hello");
+        assert_eq!(&*from("hello").into_boxed_str(), "hello");
+        assert_eq!(from("hello").leak(), "hello");
+        assert_eq!(from("héllo").into_chars().rev().collect::<String>(), "olléh");
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut s = MinimalString::new();
+        s.reserve_exact(10);
+        assert!(s.capacity() >= 10);
+        assert!(s.try_reserve(100).is_ok());
+        assert!(s.capacity() >= 100);
+        assert!(s.try_reserve_exact(200).is_ok());
+        assert!(s.capacity() >= 200);
+        assert!(s.try_reserve(usize::MAX).is_err());
+        assert!(s.try_reserve_exact(usize::MAX).is_err());
+        s.shrink_to_fit();
+        s.shrink_to(10);
+        assert!(s.capacity() >= 10);
+    }
+
+    #[test]
+    fn test_push() {
+        let mut s = MinimalString::new();
+        s.push('a');
+        s.push('é');
+        s.push('𝄞');
+        assert_eq!(s, "aé𝄞");
+        assert!(s.try_push('!').is_ok());
+        assert!(s.try_push_str("?!").is_ok());
+        assert_eq!(s, "aé𝄞!?!");
+        s.push_utf8_lossy(b"\xF0\x9D");
+        assert_eq!(s, "aé𝄞!?!\u{FFFD}");
+    }
+
+    #[test]
+    fn test_write_fmt() {
+        let mut s = MinimalString::new();
+        write!(s, "{}-{}", 1, 2).unwrap();
+        assert_eq!(s, "1-2");
+    }
+
+    #[test]
+    fn test_as() {
+        let mut s = from("héllo");
+        assert_eq!(s.as_str(), "héllo");
+        assert_eq!(s.as_bytes(), "héllo".as_bytes());
+        assert_eq!(s.len(), 6);
+        s.as_mut_str().make_ascii_uppercase();
+        assert_eq!(s, "HéLLO");
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut s = from("aé𝄞");
+        s.extend_from_within(1..);
+        assert_eq!(s, "aé𝄞é𝄞");
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut s = from("aé");
+        assert_eq!(s.pop(), Some('é'));
+        assert_eq!(s.pop(), Some('a'));
+        assert_eq!(s.pop(), None);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut s = from("aé𝄞b");
+        assert_eq!(s.remove(1), 'é');
+        assert_eq!(s.remove(1), '𝄞');
+        assert_eq!(s, "ab");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remove_end() {
+        from("ab").remove(2);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut s = from("ac");
+        s.insert(1, 'é');
+        s.insert_str(0, "𝄞");
+        s.insert_str(s.len(), "!");
+        assert_eq!(s, "𝄞aéc!");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_str_not_char_boundary() {
+        from("é").insert_str(1, "a");
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut s = from("fooβar");
+        let t = s.split_off(3);
+        assert_eq!(s, "foo");
+        assert_eq!(t, "βar");
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut s = from("f_ö_ö𝄞_ar");
+        s.retain(|c| c != '_');
+        assert_eq!(s, "föö𝄞ar");
+    }
+
+    #[test]
+    fn test_retain_panic() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut s = from("a_é_𝄞_b");
+        let mut seen = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            s.retain(|c| {
+                seen += 1;
+                if seen == 5 {
+                    panic!("oops");
+                }
+                c != '_'
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(s, "aé𝄞_b");
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut s = from("aaaaa");
+        s.remove_matches("aa");
+        assert_eq!(s, "a");
+    }
+
+    #[test]
+    fn test_drain_and_replace_range() {
+        let mut s = from("fooβar");
+        let t: String = s.drain(1..5).collect();
+        assert_eq!(t, "ooβ");
+        assert_eq!(s, "far");
+        s.replace_range(1..2, "éé");
+        assert_eq!(s, "féér");
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut s = from("foo");
+        s.clear();
+        assert!(s.is_empty());
+    }
+
+    mod conformance {
+        use super::MinimalString;
+
+        string_ext_conformance_tests!(MinimalString);
+    }
+}